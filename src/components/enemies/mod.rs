@@ -1,14 +1,80 @@
-use crate::graphics::{Pos, Render};
+use crate::{
+    game::{EventBus, Profile},
+    graphics::{Pos, Render},
+};
 
+pub mod script;
 pub mod walls;
+pub use script::{Script, Scripted};
 pub use walls::Walls;
 
-use super::DynComp;
+use super::{DynComp, GameRng};
 
 /// Defines behavior of components that can hit the player.
 pub trait Enemy: Render + DynComp {
-    /// Verify if the component hit the given position.
-    fn hits(&self, pos: &Pos) -> bool;
+    /// Returns the damage that the component deals to a player at the given position (`0` if it
+    /// doesn't hit the position).
+    fn hits(&self, pos: &Pos) -> u16;
+
+    /// Returns the name of the enemy (e.g. to tell what killed the player).
+    fn name(&self) -> &str;
+
+    /// Returns the obstacles on the screen, so they can be avoided (e.g. by a bot). Enemies that
+    /// can't be avoided don't need to implement it.
+    fn obstacles(&self) -> Vec<Obstacle> {
+        Vec::new()
+    }
+
+    /// Reseeds the random generation of the component from the game `rng`. Enemies that aren't
+    /// random don't need to implement it.
+    fn reseed(&mut self, _rng: &mut GameRng) {}
+
+    /// Adapts the component to the difficulty `profile`. Enemies that don't depend on the
+    /// difficulty don't need to implement it.
+    fn set_profile(&mut self, _profile: &Profile) {}
+
+    /// Tells the position of the `player` before each update. Enemies that don't follow the
+    /// player don't need to implement it.
+    fn watch(&mut self, _player: &Pos) {}
+
+    /// Publishes the events of the last update (e.g. [`GameEvent::ObstacleSpawned`]). Enemies
+    /// without events don't need to implement it.
+    ///
+    /// [`GameEvent::ObstacleSpawned`]: crate::game::GameEvent::ObstacleSpawned
+    fn publish(&mut self, _events: &mut EventBus) {}
+
+    /// Returns how the enemy is displayed, if it can be changed (see [`Enemy::restyle`]).
+    fn style(&self) -> Option<Style> {
+        None
+    }
+
+    /// Changes how the enemy and the objects it has spawned are displayed. Enemies without a
+    /// [`Style`] don't need to implement it.
+    fn restyle(&mut self, _style: &Style) {}
+}
+
+/// How an enemy is displayed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Style {
+    /// Character of the enemy, if it is drawn with a single character.
+    pub icon: Option<char>,
+    /// Color of the enemy, as an escape sequence (e.g. `Fg(Red).to_string()`).
+    pub color: String,
+}
+
+/// A rectangle that hits the player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Obstacle {
+    /// Leftmost column of the obstacle.
+    pub col: u16,
+    /// How many columns (from the leftmost one to the right) hit the player.
+    pub width: u16,
+    /// Top row of the obstacle.
+    pub row: u16,
+    /// How many rows (from the top row down) the obstacle takes.
+    pub height: u16,
+    /// How many columns the obstacle moves to the left at each world step.
+    pub shift: u16,
 }
 
 /// Components that can hit the player.
@@ -27,6 +93,37 @@ impl Enemies {
     pub fn add_enemy<E: Enemy + 'static>(&mut self, enemy: E) {
         self.comps.push(Box::new(enemy));
     }
+
+    /// Returns the first enemy that hits the given position, if any.
+    pub fn hitter(&self, pos: &Pos) -> Option<&dyn Enemy> {
+        self.comps
+            .iter()
+            .find(|comp| comp.hits(pos) > 0)
+            .map(|comp| comp.as_ref())
+    }
+
+    /// Displays each enemy as the enemy at the same position of `look`, which must have the same
+    /// enemies. Otherwise nothing changes and returns what is different.
+    pub fn restyle(&mut self, look: &Enemies) -> Result<(), String> {
+        let names = |enemies: &Enemies| -> Vec<String> {
+            enemies.comps.iter().map(|c| c.name().to_string()).collect()
+        };
+        let (old, new) = (names(self), names(look));
+        if old != new {
+            return Err(format!(
+                "the enemies changed from [{}] to [{}]",
+                old.join(", "),
+                new.join(", ")
+            ));
+        }
+
+        for (comp, look) in self.comps.iter_mut().zip(&look.comps) {
+            if let Some(style) = look.style() {
+                comp.restyle(&style);
+            }
+        }
+        Ok(())
+    }
 }
 
 impl DynComp for Enemies {
@@ -50,7 +147,43 @@ impl Render for Enemies {
 }
 
 impl Enemy for Enemies {
-    fn hits(&self, pos: &Pos) -> bool {
-        self.comps.iter().any(|comp| comp.hits(pos))
+    /// Returns the damage dealt by all the enemies that hit the given position.
+    fn hits(&self, pos: &Pos) -> u16 {
+        self.comps
+            .iter()
+            .fold(0, |damage, comp| damage.saturating_add(comp.hits(pos)))
+    }
+
+    fn name(&self) -> &str {
+        "enemies"
+    }
+
+    /// Returns the obstacles of all the enemies, from left to right.
+    fn obstacles(&self) -> Vec<Obstacle> {
+        let mut obstacles: Vec<_> = self
+            .comps
+            .iter()
+            .flat_map(|comp| comp.obstacles())
+            .collect();
+        obstacles.sort_by_key(|obstacle| obstacle.col);
+        obstacles
+    }
+
+    fn reseed(&mut self, rng: &mut GameRng) {
+        self.comps.iter_mut().for_each(|comp| comp.reseed(rng));
+    }
+
+    fn set_profile(&mut self, profile: &Profile) {
+        self.comps
+            .iter_mut()
+            .for_each(|comp| comp.set_profile(profile));
+    }
+
+    fn watch(&mut self, player: &Pos) {
+        self.comps.iter_mut().for_each(|comp| comp.watch(player));
+    }
+
+    fn publish(&mut self, events: &mut EventBus) {
+        self.comps.iter_mut().for_each(|comp| comp.publish(events));
     }
 }
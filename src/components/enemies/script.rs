@@ -0,0 +1,450 @@
+//! Enemies defined by [Rhai](https://rhai.rs) scripts.
+//!
+//! A script decides when and what to spawn, while the host moves the spawned objects towards the
+//! player (`shift` columns per world step, as the [`Walls`]) and checks if they hit the player.
+//! The script defines:
+//!
+//! - `fn update(api)`: called at each world step (required).
+//! - `fn init(api)`: called at the start of each run, before the first update (optional).
+//!
+//! Both are called with `this` bound to a map kept between the calls (and cleared on each run),
+//! where the script keeps its state. Code outside of the functions isn't run.
+//!
+//! The `api` object is the host API:
+//!
+//! - `api.player_col`, `api.player_row`: position of the player.
+//! - `api.floor`: row of the floor (the ground is the row above it).
+//! - `api.width`: width of the screen.
+//! - `api.step`: world steps since the start of the run.
+//! - `api.random(min, max)`: a random integer in `min..=max` (generated from the run seed).
+//! - `api.add_object(text)`: spawns an object drawn by `text` (lines split by `\n`) on the
+//!   ground, at the right edge of the screen.
+//! - `api.add_object(text, above)`: spawns the object `above` rows over the ground.
+//! - `api.add_object(text, above, damage)`: spawns an object that takes `damage` lives (1 by
+//!   default).
+//! - `api.emit(name)`: publishes a [`GameEvent::Scripted`] event.
+//! - `api.bonus(points)`: gives extra points (a [`GameEvent::Bonus`] event).
+//!
+//! Scripts are sandboxed: they can't access files, import modules, evaluate code or read the
+//! time, and each call is limited in the operations it runs, so a broken script can't freeze the
+//! game. A script that fails stops spawning objects (see [`Scripted::error`]).
+//!
+//! # Example
+//!
+//! ```
+//! use crabbit::{
+//!     components::{
+//!         enemies::{Enemy, Script, Scripted},
+//!         DynComp,
+//!     },
+//!     graphics::Pos,
+//! };
+//!
+//! let script = Script::compile("spikes", r"
+//!     fn init(api) { this.next = 3; }
+//!
+//!     fn update(api) {
+//!         this.next -= 1;
+//!         if this.next == 0 {
+//!             api.add_object(`^`);
+//!             this.next = 10;
+//!         }
+//!     }
+//! ").unwrap();
+//! let mut spikes = Scripted::new(script, Pos { col: 80, row: 20 }, 2);
+//! for _ in 0..3 {
+//!     spikes.update();
+//! }
+//! assert_eq!(spikes.obstacles().len(), 1);
+//! assert_eq!(spikes.hits(&Pos { col: 80, row: 19 }), 1);
+//! assert!(spikes.error().is_none());
+//! ```
+//!
+//! [`Walls`]: super::Walls
+
+use rand::{Rng, SeedableRng};
+use rhai::{
+    packages::{BasicArrayPackage, BasicMapPackage, BasicMathPackage, CorePackage, LogicPackage},
+    packages::{MoreStringPackage, Package},
+    CallFnOptions, Dynamic, Engine, EvalAltResult, Map, Scope, AST, INT,
+};
+use std::{cell::RefCell, collections::VecDeque, ops::Range, rc::Rc};
+use termion::color::{Color, Fg, Red};
+
+use crate::{
+    components::{
+        enemies::{Enemy, Obstacle, Style},
+        DynComp, GameRng,
+    },
+    game::{EventBus, GameEvent},
+    graphics::{
+        object::{Obj, Sprite},
+        Pos, Render,
+    },
+};
+
+/// Result of a host API call.
+type ApiResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// A compiled enemy script.
+#[derive(Debug, Clone)]
+pub struct Script {
+    name: String,
+    ast: AST,
+}
+
+impl Script {
+    // operations that a single call of a script can run
+    const MAX_OPERATIONS: u64 = 100_000;
+
+    /// Compiles the `text` of the script of the enemy called `name`. On error returns what is
+    /// wrong.
+    pub fn compile(name: &str, text: &str) -> Result<Self, String> {
+        let ast = Self::engine().compile(text).map_err(|e| e.to_string())?;
+        if !ast.iter_functions().any(|f| f.name == "update") {
+            return Err("the script has no `update` function".to_string());
+        }
+        Ok(Script {
+            name: name.to_string(),
+            ast,
+        })
+    }
+
+    /// Returns the name of the enemy.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns a sandboxed engine with the host API.
+    fn engine() -> Engine {
+        let mut engine = Engine::new_raw();
+        engine.register_global_module(CorePackage::new().as_shared_module());
+        engine.register_global_module(LogicPackage::new().as_shared_module());
+        engine.register_global_module(BasicMathPackage::new().as_shared_module());
+        engine.register_global_module(BasicArrayPackage::new().as_shared_module());
+        engine.register_global_module(BasicMapPackage::new().as_shared_module());
+        engine.register_global_module(MoreStringPackage::new().as_shared_module());
+        engine
+            .disable_symbol("eval")
+            .disable_symbol("import")
+            .set_max_operations(Self::MAX_OPERATIONS)
+            .set_max_call_levels(32)
+            .set_max_expr_depths(64, 32)
+            .set_max_string_size(4096)
+            .set_max_array_size(1024)
+            .set_max_map_size(256);
+
+        engine
+            .register_type_with_name::<Api>("Api")
+            .register_get("player_col", |api: &mut Api| {
+                api.get(|f| f.player.col as INT)
+            })
+            .register_get("player_row", |api: &mut Api| {
+                api.get(|f| f.player.row as INT)
+            })
+            .register_get("floor", |api: &mut Api| api.get(|f| f.floor as INT))
+            .register_get("width", |api: &mut Api| api.get(|f| f.width as INT))
+            .register_get("step", |api: &mut Api| api.get(|f| f.step as INT))
+            .register_fn("random", Api::random)
+            .register_fn("add_object", |api: &mut Api, text: &str| {
+                api.spawn(text, 0, 1)
+            })
+            .register_fn("add_object", |api: &mut Api, text: &str, above: INT| {
+                api.spawn(text, above, 1)
+            })
+            .register_fn("add_object", Api::spawn)
+            .register_fn("emit", Api::emit)
+            .register_fn("bonus", Api::bonus);
+        engine
+    }
+}
+
+/// An object requested by a script.
+struct Spawn {
+    sprite: Sprite,
+    above: u16,
+    damage: u16,
+}
+
+/// What a script can see and do in a call.
+struct Frame {
+    name: String,
+    player: Pos,
+    floor: u16,
+    width: u16,
+    step: u64,
+    rng: GameRng,
+    spawns: Vec<Spawn>,
+    events: Vec<GameEvent>,
+}
+
+/// The host API handed to the scripts (see the [module docs](self)).
+#[derive(Clone)]
+struct Api(Rc<RefCell<Frame>>);
+
+impl Api {
+    /// Returns a value of the frame.
+    fn get<T>(&self, f: impl Fn(&Frame) -> T) -> T {
+        f(&self.0.borrow())
+    }
+
+    fn random(&mut self, min: INT, max: INT) -> ApiResult<INT> {
+        if min > max {
+            return Err(format!("random: empty range {}..={}", min, max).into());
+        }
+        Ok(self.0.borrow_mut().rng.gen_range(min..=max))
+    }
+
+    fn spawn(&mut self, text: &str, above: INT, damage: INT) -> ApiResult<()> {
+        let lines: Vec<_> = text.lines().collect();
+        let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        let mut frame = self.0.borrow_mut();
+        if width == 0 || width > frame.width as usize {
+            return Err(format!("add_object: invalid object {:?}", text).into());
+        }
+        let above =
+            u16::try_from(above).map_err(|_| format!("add_object: invalid height {}", above))?;
+        let damage =
+            u16::try_from(damage).map_err(|_| format!("add_object: invalid damage {}", damage))?;
+
+        let mut chars = Vec::with_capacity(width * lines.len());
+        for line in lines {
+            let len = chars.len();
+            chars.extend(line.chars());
+            chars.resize(len + width, ' ');
+        }
+        frame.spawns.push(Spawn {
+            sprite: Sprite::new(chars, width as u16),
+            above,
+            damage,
+        });
+        Ok(())
+    }
+
+    fn emit(&mut self, name: &str) {
+        let mut frame = self.0.borrow_mut();
+        let enemy = frame.name.clone();
+        frame.events.push(GameEvent::Scripted {
+            enemy,
+            name: name.to_string(),
+        });
+    }
+
+    fn bonus(&mut self, points: INT) -> ApiResult<()> {
+        let points =
+            u32::try_from(points).map_err(|_| format!("bonus: invalid points {}", points))?;
+        self.0.borrow_mut().events.push(GameEvent::Bonus(points));
+        Ok(())
+    }
+}
+
+/// An enemy defined by a [`Script`] (see the [module docs](self)).
+pub struct Scripted {
+    script: Script,
+    engine: Engine,
+    state: Dynamic,
+    frame: Rc<RefCell<Frame>>,
+    started: bool,
+    error: Option<String>,
+    pos: Pos,
+    color: String,
+    shift: u16,
+    objs: VecDeque<(Obj, u16)>,
+    spawned: Vec<Obstacle>,
+}
+
+impl Scripted {
+    /// Returns a new `Scripted` enemy that spawns objects on the floor at `pos` (the right edge
+    /// of the screen) and moves them `shift` columns per world step.
+    pub fn new(script: Script, pos: Pos, shift: u16) -> Self {
+        let frame = Frame {
+            name: script.name.clone(),
+            player: Pos { col: 1, row: 1 },
+            floor: pos.row,
+            width: pos.col,
+            step: 0,
+            rng: GameRng::seed_from_u64(rand::random()),
+            spawns: Vec::new(),
+            events: Vec::new(),
+        };
+        Scripted {
+            script,
+            engine: Script::engine(),
+            state: Dynamic::from_map(Map::new()),
+            frame: Rc::new(RefCell::new(frame)),
+            started: false,
+            error: None,
+            pos,
+            color: Fg(Red).to_string(),
+            shift: shift.max(1),
+            objs: VecDeque::new(),
+            spawned: Vec::new(),
+        }
+    }
+
+    /// Changes the color of the objects (red by default).
+    pub fn colored<C: Color>(mut self, color: C) -> Self {
+        self.color = Fg(color).to_string();
+        self
+    }
+
+    /// Returns the error that stopped the script, if any.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Calls the script function `name`, unless the script failed. A failure stops the script.
+    fn call(&mut self, name: &str) {
+        if self.error.is_some() {
+            return;
+        }
+        let options = CallFnOptions::new()
+            .eval_ast(false)
+            .bind_this_ptr(&mut self.state);
+        let api = Api(Rc::clone(&self.frame));
+        let result = self.engine.call_fn_with_options::<Dynamic>(
+            options,
+            &mut Scope::new(),
+            &self.script.ast,
+            name,
+            (api,),
+        );
+        if let Err(e) = result {
+            self.error = Some(format!("{}: {}", name, e));
+        }
+    }
+
+    /// Places the objects spawned by the script.
+    fn place_spawns(&mut self) {
+        let spawns = std::mem::take(&mut self.frame.borrow_mut().spawns);
+        for spawn in spawns {
+            let (width, height) = spawn.sprite.size();
+            let pos = Pos {
+                col: (self.pos.col + 1).saturating_sub(width).max(1),
+                row: self.pos.row.saturating_sub(spawn.above + height).max(1),
+            };
+            let obj = Obj {
+                pos,
+                sprite: spawn.sprite,
+                color: self.color.clone(),
+            };
+            self.spawned.push(self.obstacle(&obj));
+            self.objs.push_back((obj, spawn.damage));
+        }
+    }
+
+    /// Returns the columns hit by an object: the objects move `shift` columns at once, so they
+    /// hit the columns they skip as well.
+    fn hit_cols(&self, obj: &Obj) -> Range<u16> {
+        let (width, _) = obj.sprite.size();
+        obj.pos.col..(obj.pos.col + width + self.shift.max(1) - 1)
+    }
+
+    /// Returns the obstacle of an object.
+    fn obstacle(&self, obj: &Obj) -> Obstacle {
+        let cols = self.hit_cols(obj);
+        Obstacle {
+            col: cols.start,
+            width: cols.len() as u16,
+            row: obj.pos.row,
+            height: obj.sprite.size().1,
+            shift: self.shift,
+        }
+    }
+}
+
+impl Render for Scripted {
+    fn render(&self, out: &mut crate::graphics::TermOut) {
+        self.objs.iter().for_each(|(obj, _)| obj.render(out));
+    }
+
+    fn erase(&self, out: &mut crate::graphics::TermOut) {
+        self.objs.iter().for_each(|(obj, _)| obj.erase(out));
+    }
+}
+
+impl DynComp for Scripted {
+    fn update(&mut self) {
+        for (obj, _) in &mut self.objs {
+            obj.pos.col = obj.pos.col.saturating_sub(self.shift);
+        }
+        self.objs.retain(|(obj, _)| obj.pos.col > 0);
+
+        if !self.started {
+            self.started = true;
+            if self.script.ast.iter_functions().any(|f| f.name == "init") {
+                self.call("init");
+            }
+        }
+        self.call("update");
+        self.frame.borrow_mut().step += 1;
+        self.place_spawns();
+    }
+
+    fn reset(&mut self) {
+        self.objs.clear();
+        self.spawned.clear();
+        self.state = Dynamic::from_map(Map::new());
+        self.started = false;
+        let mut frame = self.frame.borrow_mut();
+        frame.step = 0;
+        frame.spawns.clear();
+        frame.events.clear();
+    }
+}
+
+impl Enemy for Scripted {
+    fn hits(&self, pos: &Pos) -> u16 {
+        let hit = |(obj, _): &&(Obj, u16)| {
+            let (_, height) = obj.sprite.size();
+            let rows = obj.pos.row..(obj.pos.row + height);
+            self.hit_cols(obj).contains(&pos.col) && rows.contains(&pos.row)
+        };
+        self.objs
+            .iter()
+            .filter(hit)
+            .fold(0, |damage, (_, d)| damage.saturating_add(*d))
+    }
+
+    fn name(&self) -> &str {
+        self.script.name()
+    }
+
+    fn obstacles(&self) -> Vec<Obstacle> {
+        self.objs
+            .iter()
+            .map(|(obj, _)| self.obstacle(obj))
+            .collect()
+    }
+
+    fn style(&self) -> Option<Style> {
+        Some(Style {
+            icon: None,
+            color: self.color.clone(),
+        })
+    }
+
+    /// Changes the color of the objects, including those on the screen.
+    fn restyle(&mut self, style: &Style) {
+        self.color.clone_from(&style.color);
+        for (obj, _) in &mut self.objs {
+            obj.color.clone_from(&self.color);
+        }
+    }
+
+    fn reseed(&mut self, rng: &mut GameRng) {
+        self.frame.borrow_mut().rng = GameRng::seed_from_u64(rng.gen());
+    }
+
+    fn watch(&mut self, player: &Pos) {
+        self.frame.borrow_mut().player = *player;
+    }
+
+    fn publish(&mut self, events: &mut EventBus) {
+        self.spawned
+            .drain(..)
+            .for_each(|obstacle| events.publish(GameEvent::ObstacleSpawned(obstacle)));
+        let scripted = std::mem::take(&mut self.frame.borrow_mut().events);
+        scripted.into_iter().for_each(|event| events.publish(event));
+    }
+}
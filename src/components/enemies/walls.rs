@@ -1,9 +1,13 @@
-use rand::{distributions::Bernoulli, prelude::Distribution};
-use std::collections::VecDeque;
-use termion::color::{Fg, Red};
+use rand::{distributions::Bernoulli, prelude::Distribution, Rng, SeedableRng};
+use std::{collections::VecDeque, ops::RangeInclusive};
+use termion::color::{Color, Fg, Red};
 
 use crate::{
-    components::{enemies::Enemy, DynComp},
+    components::{
+        enemies::{Enemy, Obstacle, Style},
+        DynComp, GameRng,
+    },
+    game::{EventBus, GameEvent, Profile},
     graphics::{
         object::{Obj, Sprite},
         Pos, Render,
@@ -12,24 +16,26 @@ use crate::{
 
 /// Kinds of wall.
 enum Wall {
-    Big,
-    Small,
+    /// A wall of the given height.
+    Solid(u16),
     Void,
 }
 
 impl Wall {
-    pub fn to_obj(&self, sprite_char: char, mut pos: Pos) -> Option<Obj> {
+    pub fn to_obj(&self, sprite_char: char, color: &str, mut pos: Pos) -> Option<Obj> {
         let h = match self {
-            Self::Big => 2,
-            Self::Small => 1,
+            Self::Solid(h) => *h,
             Self::Void => return None,
         };
         pos.row = pos.row.checked_sub(h).unwrap_or(1);
 
         let ascii_matrix = vec![sprite_char; h as usize];
         let sprite = Sprite::new(ascii_matrix, 1);
-        let obj = Obj::new(pos, sprite, &Fg(Red));
-        Some(obj)
+        Some(Obj {
+            pos,
+            sprite,
+            color: color.to_string(),
+        })
     }
 }
 
@@ -37,36 +43,58 @@ impl Wall {
 pub struct Walls {
     pos: Pos,
     icon: char,
+    color: String,
     shift: u16,
     queue: VecDeque<Wall>,
     objs: VecDeque<Obj>,
+    spawned: Vec<Obstacle>,
     wall_prob: Bernoulli,
+    heights: RangeInclusive<u16>,
+    rng: GameRng,
 }
 
 impl Walls {
+    // damage dealt by a wall
+    const DAMAGE: u16 = 1;
+
     pub fn new(icon: char, pos: Pos, shift: u16) -> Self {
         Walls {
             pos,
             icon,
+            color: Fg(Red).to_string(),
             shift,
             queue: VecDeque::with_capacity(8),
             objs: VecDeque::new(),
-            // chance of having a wall: 16% per chunk
-            wall_prob: Bernoulli::from_ratio(16, 100).expect("Failed to create Bernoulli."),
+            spawned: Vec::new(),
+            wall_prob: Self::prob(Profile::default().wall_prob),
+            heights: 1..=2,
+            rng: GameRng::seed_from_u64(rand::random()),
         }
     }
 
+    /// Changes the color of the walls (red by default).
+    pub fn colored<C: Color>(mut self, color: C) -> Self {
+        self.color = Fg(color).to_string();
+        self
+    }
+
+    /// Returns the distribution of having a wall with the given chance (in percent).
+    fn prob(percent: u32) -> Bernoulli {
+        Bernoulli::from_ratio(percent.min(100), 100).expect("Failed to create Bernoulli.")
+    }
+
     /// Generate a chunk of walls (4 walls) plus a gap (4 spaces).
+    ///
+    /// The walls at the edges of the chunk are always the lowest ones.
     fn gen_walls(&mut self) {
         // walls
         for i in 0..4 {
-            let has_wall = self.wall_prob.sample(&mut rand::thread_rng());
+            let has_wall = self.wall_prob.sample(&mut self.rng);
             if has_wall && (i == 0 || i == 3) {
-                self.queue.push_back(Wall::Small);
+                self.queue.push_back(Wall::Solid(*self.heights.start()));
             } else if has_wall {
-                let is_big: bool = rand::random();
-                let wall = if is_big { Wall::Big } else { Wall::Small };
-                self.queue.push_back(wall);
+                let height = self.rng.gen_range(self.heights.clone());
+                self.queue.push_back(Wall::Solid(height));
             } else {
                 self.queue.push_back(Wall::Void);
             }
@@ -85,6 +113,22 @@ impl Walls {
         });
     }
 
+    /// Returns the column in which a wall object hits the player.
+    fn hit_col(&self, obj: &Obj) -> u16 {
+        obj.pos.col + (obj.pos.col % self.shift)
+    }
+
+    /// Returns the obstacle of a wall object.
+    fn obstacle(&self, obj: &Obj) -> Obstacle {
+        Obstacle {
+            col: self.hit_col(obj),
+            width: 1,
+            row: obj.pos.row,
+            height: obj.sprite.size().1,
+            shift: self.shift,
+        }
+    }
+
     /// Remove objects that are not on the screen.
     fn clean_objs(&mut self) {
         if let Some(obj) = self.objs.front() {
@@ -116,7 +160,8 @@ impl DynComp for Walls {
 
         match self.queue.pop_front() {
             Some(w) => {
-                if let Some(obj) = w.to_obj(self.icon, self.pos.clone()) {
+                if let Some(obj) = w.to_obj(self.icon, &self.color, self.pos) {
+                    self.spawned.push(self.obstacle(&obj));
                     self.objs.push_back(obj);
                 }
             }
@@ -126,24 +171,67 @@ impl DynComp for Walls {
 
     fn reset(&mut self) {
         self.objs.clear();
+        self.queue.clear();
+        self.spawned.clear();
     }
 }
 
 impl Enemy for Walls {
-    fn hits(&self, pos: &Pos) -> bool {
+    fn hits(&self, pos: &Pos) -> u16 {
         for obj in &self.objs {
-            let col = obj.pos.col + (obj.pos.col % self.shift);
+            let col = self.hit_col(obj);
             let (_, obj_height) = obj.sprite.size();
             let row_range = obj.pos.row..(obj.pos.row + obj_height);
 
             if col > pos.col {
-                return false;
+                return 0;
             }
 
             if col == pos.col && row_range.contains(&pos.row) {
-                return true;
+                return Self::DAMAGE;
             }
         }
-        false
+        0
+    }
+
+    fn name(&self) -> &str {
+        "wall"
+    }
+
+    fn style(&self) -> Option<Style> {
+        Some(Style {
+            icon: Some(self.icon),
+            color: self.color.clone(),
+        })
+    }
+
+    /// Changes the icon and the color of the walls, including those on the screen.
+    fn restyle(&mut self, style: &Style) {
+        self.icon = style.icon.unwrap_or(self.icon);
+        self.color.clone_from(&style.color);
+        for obj in &mut self.objs {
+            let (_, height) = obj.sprite.size();
+            obj.sprite.set_ascii(vec![self.icon; height as usize]);
+            obj.color.clone_from(&self.color);
+        }
+    }
+
+    fn obstacles(&self) -> Vec<Obstacle> {
+        self.objs.iter().map(|obj| self.obstacle(obj)).collect()
+    }
+
+    fn reseed(&mut self, rng: &mut GameRng) {
+        self.rng = GameRng::seed_from_u64(rng.gen());
+    }
+
+    fn set_profile(&mut self, profile: &Profile) {
+        self.wall_prob = Self::prob(profile.wall_prob);
+        self.heights = profile.min_wall..=profile.max_wall.max(profile.min_wall);
+    }
+
+    fn publish(&mut self, events: &mut EventBus) {
+        self.spawned
+            .drain(..)
+            .for_each(|obstacle| events.publish(GameEvent::ObstacleSpawned(obstacle)));
     }
 }
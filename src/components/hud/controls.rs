@@ -0,0 +1,180 @@
+use termion::{cursor::Goto, event::Key, style};
+
+use crate::{
+    graphics::{Pos, Render, Size, TermOut},
+    input::{keys, Act, KeyMap},
+};
+
+/// What the controls screen is doing.
+#[derive(Debug, PartialEq)]
+enum ControlsState {
+    Closed,
+    Browsing,
+    Capturing,
+}
+
+/// A screen that shows the key bindings and lets the user edit them.
+///
+/// The actions are selected with the arrow keys, `Enter` binds a new key to the selected action,
+/// `Backspace` removes its last key (but not the only one, see [`KeyMap::unbind_last`]) and `Esc`
+/// leaves the screen.
+#[derive(Debug)]
+pub struct Controls {
+    keys: KeyMap,
+    changed: bool,
+    selected: usize,
+    state: ControlsState,
+    message: String,
+    pos: Pos,
+}
+
+impl Controls {
+    // screen title
+    const TITLE: &'static str = "Controls";
+    // usage help
+    const HELP: &'static str = "up/down: select  enter: add key  backspace: remove  esc: back";
+    // screen width (in characters)
+    const WIDTH: u16 = 64;
+    // screen height: title, blank line, actions, blank line, help and message.
+    const HEIGHT: u16 = Act::ALL.len() as u16 + 5;
+
+    /// Returns a new (closed) instance of `Controls` centered on a screen of the given `size`.
+    pub fn new(size: &Size) -> Self {
+        let col = (size.width.saturating_sub(Self::WIDTH) / 2) + 1;
+        let row = (size.height.saturating_sub(Self::HEIGHT) / 2).max(1);
+
+        Controls {
+            keys: KeyMap::default(),
+            changed: false,
+            selected: 0,
+            state: ControlsState::Closed,
+            message: String::new(),
+            pos: Pos { col, row },
+        }
+    }
+
+    /// Opens the screen to edit `keys`.
+    pub fn open(&mut self, keys: KeyMap) {
+        self.keys = keys;
+        self.changed = false;
+        self.selected = 0;
+        self.message.clear();
+        self.state = ControlsState::Browsing;
+    }
+
+    /// Returns `true` if the screen is open and `false` otherwise.
+    pub fn is_open(&self) -> bool {
+        self.state != ControlsState::Closed
+    }
+
+    /// Shows `msg` at the bottom of the screen.
+    pub fn set_message(&mut self, msg: String) {
+        self.message = msg;
+    }
+
+    /// Handles a key pressed while the screen is open.
+    ///
+    /// When the screen is closed returns the edited key bindings if they have changed.
+    pub fn handle(&mut self, key: Key) -> Option<KeyMap> {
+        let act = Act::ALL[self.selected];
+
+        match self.state {
+            ControlsState::Closed => (),
+            ControlsState::Capturing => {
+                self.state = ControlsState::Browsing;
+                self.message = match key {
+                    Key::Esc => String::new(),
+                    key => match self.keys.bind(act, key) {
+                        Ok(()) => {
+                            self.changed = true;
+                            String::new()
+                        }
+                        Err(e) => format!("Can't bind: {}.", e),
+                    },
+                };
+            }
+            ControlsState::Browsing => match key {
+                Key::Up => self.selected = self.selected.saturating_sub(1),
+                Key::Down => self.selected = (self.selected + 1).min(Act::ALL.len() - 1),
+                Key::Char('\n') => {
+                    self.state = ControlsState::Capturing;
+                    self.message = format!("Press a key for \"{}\" (esc to cancel).", act.name());
+                }
+                Key::Backspace | Key::Delete => match self.keys.unbind_last(act) {
+                    Some(_) => {
+                        self.changed = true;
+                        self.message.clear();
+                    }
+                    None => {
+                        self.message = format!("Can't remove the only key of \"{}\".", act.name())
+                    }
+                },
+                Key::Esc => {
+                    self.state = ControlsState::Closed;
+                    if self.changed {
+                        return Some(self.keys.clone());
+                    }
+                }
+                _ => (),
+            },
+        }
+        None
+    }
+
+    /// Returns the lines of the screen.
+    fn lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("{:^1$}", Self::TITLE, Self::WIDTH as usize),
+            String::new(),
+        ];
+        for (i, act) in Act::ALL.into_iter().enumerate() {
+            let cursor = if i == self.selected { '>' } else { ' ' };
+            let keys: Vec<_> = self.keys.keys(act).iter().map(|k| keys::name(*k)).collect();
+            lines.push(format!(" {} {:<10}{}", cursor, act.name(), keys.join(", ")));
+        }
+        lines.push(String::new());
+        lines.push(format!(" {}", Self::HELP));
+        lines.push(format!(" {}", self.message));
+        lines
+    }
+}
+
+impl Render for Controls {
+    fn render(&self, out: &mut TermOut) {
+        if !self.is_open() {
+            return;
+        }
+
+        let width = Self::WIDTH as usize;
+        for (row, line) in (self.pos.row..).zip(self.lines()) {
+            let line: String = line.chars().take(width).collect();
+            let selected = row == self.pos.row + 2 + self.selected as u16;
+            let invert = if selected {
+                style::Invert.to_string()
+            } else {
+                String::new()
+            };
+            write!(
+                out,
+                "{}{}{:<w$}{}",
+                Goto(self.pos.col, row),
+                invert,
+                line,
+                style::Reset,
+                w = width
+            )
+            .unwrap();
+        }
+    }
+
+    fn erase(&self, out: &mut TermOut) {
+        if !self.is_open() {
+            return;
+        }
+
+        let overwrite = " ".repeat(Self::WIDTH as usize);
+        for row in self.pos.row..(self.pos.row + Self::HEIGHT) {
+            write!(out, "{}{}", Goto(self.pos.col, row), overwrite).unwrap();
+        }
+    }
+}
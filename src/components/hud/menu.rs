@@ -0,0 +1,272 @@
+use termion::{cursor::Goto, event::Key, style};
+
+use crate::graphics::{Pos, Render, Size, TermOut};
+
+/// An entry of a [`Menu`].
+#[derive(Debug)]
+struct Entry<T> {
+    label: String,
+    item: T,
+    question: Option<String>,
+}
+
+/// A list of entries displayed below the center of the screen, over the game.
+///
+/// The entries are selected with the arrow keys (or a mouse click) and chosen with `Enter`. An
+/// entry that can't be undone asks a question first, answered with the arrow keys and `Enter`
+/// (or `y` and `n`), so it isn't chosen by mistake.
+///
+/// # Example
+///
+/// ```
+/// use termion::event::Key;
+/// use crabbit::{components::hud::Menu, graphics::Size};
+///
+/// let size = Size { width: 80, height: 24 };
+/// let mut menu = Menu::new(&size)
+///     .entry("Resume", 'r')
+///     .confirmed("Quit", 'q', "Quit the game?");
+///
+/// assert_eq!(menu.handle(Key::Char('\n')), Some('r'));
+///
+/// menu.handle(Key::Down);
+/// assert_eq!(menu.handle(Key::Char('\n')), None);
+/// assert!(menu.is_asking());
+/// // the answer is "no" unless changed
+/// assert_eq!(menu.handle(Key::Char('\n')), None);
+/// menu.handle(Key::Char('\n'));
+/// assert_eq!(menu.handle(Key::Char('y')), Some('q'));
+/// ```
+#[derive(Debug)]
+pub struct Menu<T> {
+    entries: Vec<Entry<T>>,
+    selected: usize,
+    answer: Option<bool>,
+    size: Size,
+}
+
+impl<T: Copy + PartialEq> Menu<T> {
+    // usage help
+    const HELP: &'static str = "up/down: select  enter: choose";
+    // answers of a question
+    const YES: &'static str = "[ Yes ]";
+    const NO: &'static str = "[ No ]";
+
+    /// Returns a new `Menu` without entries for a screen of the given `size`.
+    pub fn new(size: &Size) -> Self {
+        Menu {
+            entries: Vec::new(),
+            selected: 0,
+            answer: None,
+            size: size.clone(),
+        }
+    }
+
+    /// Adds an entry that chooses `item`.
+    pub fn entry(mut self, label: &str, item: T) -> Self {
+        self.entries.push(Entry {
+            label: label.to_string(),
+            item,
+            question: None,
+        });
+        self
+    }
+
+    /// Adds an entry that chooses `item` once the `question` is answered with "yes".
+    pub fn confirmed(mut self, label: &str, item: T, question: &str) -> Self {
+        self.entries.push(Entry {
+            label: label.to_string(),
+            item,
+            question: Some(question.to_string()),
+        });
+        self
+    }
+
+    /// Changes the label of the entry of `item`, if any.
+    pub fn relabel(&mut self, item: T, label: &str) {
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.item == item) {
+            entry.label = label.to_string();
+        }
+    }
+
+    /// Returns the selected item.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the menu has no entries.
+    pub fn selected(&self) -> T {
+        self.entries[self.selected].item
+    }
+
+    /// Selects the entry of `item`, if any, dropping the question being asked.
+    pub fn select(&mut self, item: T) {
+        if let Some(i) = self.entries.iter().position(|e| e.item == item) {
+            self.selected = i;
+        }
+        self.answer = None;
+    }
+
+    /// Returns `true` if the menu is asking the question of the selected entry.
+    pub fn is_asking(&self) -> bool {
+        self.answer.is_some()
+    }
+
+    /// Handles a key pressed on the menu. Returns the chosen item, if any.
+    ///
+    /// `Esc` drops the question being asked, other keys are ignored.
+    pub fn handle(&mut self, key: Key) -> Option<T> {
+        if self.entries.is_empty() {
+            return None;
+        }
+
+        match (self.answer, key) {
+            (Some(_), Key::Left | Key::Right | Key::Up | Key::Down | Key::Char('\t')) => {
+                self.answer = self.answer.map(|yes| !yes);
+            }
+            (Some(true), Key::Char('\n')) | (Some(_), Key::Char('y')) => {
+                self.answer = None;
+                return Some(self.selected());
+            }
+            (Some(_), Key::Char('\n' | 'n') | Key::Esc) => self.answer = None,
+            (None, Key::Up) => self.selected = self.selected.saturating_sub(1),
+            (None, Key::Down) => self.selected = (self.selected + 1).min(self.entries.len() - 1),
+            (None, Key::Char('\n')) => return self.choose(),
+            _ => (),
+        }
+        None
+    }
+
+    /// Handles a click at `pos`: selects and chooses the entry at `pos` or answers the question
+    /// being asked. Returns the chosen item, if any.
+    pub fn click(&mut self, pos: &Pos) -> Option<T> {
+        let (col, row) = (self.pos().col, self.pos().row);
+        if !(col..col + self.width()).contains(&pos.col) {
+            return None;
+        }
+
+        if self.answer.is_some() {
+            let answer_row = row + self.entries.len() as u16 + 1;
+            if pos.row == answer_row {
+                let yes = self.answer_col(Self::YES);
+                let no = self.answer_col(Self::NO);
+                if (yes..yes + Self::YES.len() as u16).contains(&pos.col) {
+                    self.answer = None;
+                    return Some(self.selected());
+                } else if (no..no + Self::NO.len() as u16).contains(&pos.col) {
+                    self.answer = None;
+                }
+            }
+            return None;
+        }
+
+        let i = pos.row.checked_sub(row)? as usize;
+        if i < self.entries.len() {
+            self.selected = i;
+            return self.choose();
+        }
+        None
+    }
+
+    /// Chooses the selected entry, asking its question first (if it has one).
+    fn choose(&mut self) -> Option<T> {
+        if self.entries[self.selected].question.is_some() {
+            self.answer = Some(false);
+            None
+        } else {
+            Some(self.selected())
+        }
+    }
+
+    /// Returns the position of the top left corner of the menu, centered two rows below the
+    /// center of the screen.
+    fn pos(&self) -> Pos {
+        Pos {
+            col: (self.size.width.saturating_sub(self.width()) / 2) + 1,
+            row: self.size.height / 2 + 2,
+        }
+    }
+
+    /// Returns the width of the menu.
+    fn width(&self) -> u16 {
+        let questions = self.entries.iter().filter_map(|e| e.question.as_ref());
+        let answers = Self::YES.len() + Self::NO.len() + 4;
+        let widest = self
+            .entries
+            .iter()
+            .map(|e| e.label.chars().count())
+            .chain(questions.map(|q| q.chars().count() + answers))
+            .chain([Self::HELP.len()])
+            .max()
+            .unwrap_or(0);
+        widest as u16 + 4
+    }
+
+    /// Returns the bottom line of the menu: the question being asked with its answers or the help.
+    fn footer(&self) -> String {
+        match (self.answer, &self.entries[self.selected].question) {
+            (Some(_), Some(question)) => {
+                format!("{}  {}  {}", question, Self::YES, Self::NO)
+            }
+            _ => Self::HELP.to_string(),
+        }
+    }
+
+    /// Returns the column of an `answer` of the question being asked.
+    fn answer_col(&self, answer: &str) -> u16 {
+        let footer = self.footer();
+        let width = self.width() as usize;
+        let start = (width - footer.chars().count()) / 2;
+        let offset = footer.rfind(answer).unwrap_or(0);
+        self.pos().col + (start + offset) as u16
+    }
+}
+
+impl<T: Copy + PartialEq> Render for Menu<T> {
+    /// Renders the entries, the selected one highlighted, and the footer.
+    fn render(&self, out: &mut TermOut) {
+        if self.entries.is_empty() {
+            return;
+        }
+
+        let Pos { col, row } = self.pos();
+        let width = self.width() as usize;
+        for (i, entry) in self.entries.iter().enumerate() {
+            let highlight = if i == self.selected {
+                style::Invert.to_string()
+            } else {
+                String::new()
+            };
+            write!(
+                out,
+                "{}{}{:^w$}{}",
+                Goto(col, row + i as u16),
+                highlight,
+                entry.label,
+                style::Reset,
+                w = width
+            )
+            .unwrap();
+        }
+
+        let footer_row = row + self.entries.len() as u16;
+        write!(out, "{}{:w$}", Goto(col, footer_row), "", w = width).unwrap();
+        let footer = self.footer();
+        let mut line = format!("{:^w$}", footer, w = width);
+        if let Some(yes) = self.answer {
+            // the selected answer is highlighted
+            let answer = if yes { Self::YES } else { Self::NO };
+            let at = line.rfind(answer).unwrap_or(0);
+            let highlighted = format!("{}{}{}", style::Invert, answer, style::Reset);
+            line.replace_range(at..at + answer.len(), &highlighted);
+        }
+        write!(out, "{}{}", Goto(col, footer_row + 1), line).unwrap();
+    }
+
+    fn erase(&self, out: &mut TermOut) {
+        let Pos { col, row } = self.pos();
+        let overwrite = " ".repeat(self.width() as usize);
+        for row in row..(row + self.entries.len() as u16 + 2) {
+            write!(out, "{}{}", Goto(col, row), overwrite).unwrap();
+        }
+    }
+}
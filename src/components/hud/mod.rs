@@ -0,0 +1,531 @@
+use termion::{
+    color::{Bg, Color, Fg, LightWhite, Red, Reset, Yellow},
+    cursor::Goto,
+};
+
+use super::DynComp;
+use crate::{
+    game::{GameEvent, Subscriber},
+    graphics::{
+        object::{Obj, Sprite},
+        Pos, Render, Size,
+    },
+};
+
+pub mod controls;
+pub mod menu;
+pub mod preferences;
+pub use controls::Controls;
+pub use menu::Menu;
+pub use preferences::Preferences;
+
+/// Controls the HUD components.
+///
+/// The `Hud` has, mainly, four purposes: track user score throught the `Score` struct, hold the
+/// splash screens throught the `Splash` struct, the key bindings screen throught the `Controls`
+/// struct and the settings screen throught the `Preferences` struct.
+///
+/// Only the score, the toasts, the seed, the status line and the alert are rendered with the
+/// `Hud`, the splash screens, the controls screen and the settings screen are rendered by the
+/// scene that displays them.
+#[derive(Debug)]
+pub struct Hud {
+    score: Score,
+    splash: Splash,
+    controls: Controls,
+    preferences: Preferences,
+    seed: u64,
+    status: String,
+    health: u16,
+    lives: u16,
+    toast: String,
+    toast_steps: u16,
+    alert: Vec<String>,
+    timer: Option<u32>,
+}
+
+impl Hud {
+    // how many world steps a toast is displayed
+    const TOAST_STEPS: u16 = 40;
+
+    /// Returns a new instance of `Hud`.
+    pub fn new(splash: Splash, controls: Controls) -> Self {
+        let score = Score::new();
+        let preferences = Preferences::new(&splash.size);
+        Self {
+            score,
+            splash,
+            controls,
+            preferences,
+            seed: 0,
+            status: String::new(),
+            health: 1,
+            lives: 1,
+            toast: String::new(),
+            toast_steps: 0,
+            alert: Vec::new(),
+            timer: None,
+        }
+    }
+
+    /// Displays a short message (a toast) next to the best score for a moment.
+    pub fn toast(&mut self, msg: String) {
+        self.toast = msg;
+        self.toast_steps = Self::TOAST_STEPS;
+    }
+
+    /// Returns the column of the toast.
+    fn toast_col(&self) -> u16 {
+        self.score.best_to_string().len() as u16 + 3
+    }
+
+    /// Displays the `lines` of an alert (e.g. an error) below the status line until they are
+    /// replaced. No lines hide the alert.
+    pub fn set_alert(&mut self, lines: Vec<String>) {
+        self.alert = lines;
+    }
+
+    /// Changes the run seed displayed below the score.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    /// Returns the run seed as a formated `String`.
+    fn seed_to_string(&self) -> String {
+        format!("Seed: {}", self.seed)
+    }
+
+    /// Changes the seconds left displayed next to the seed (e.g. in a time attack). `None` hides
+    /// them.
+    pub fn set_timer(&mut self, seconds: Option<u32>) {
+        self.timer = seconds;
+    }
+
+    /// Returns the seconds left as a formated `String` (empty if there is no timer).
+    fn timer_to_string(&self) -> String {
+        self.timer
+            .map(|seconds| format!("Time: {}s", seconds))
+            .unwrap_or_default()
+    }
+
+    /// Returns the column of the timer.
+    fn timer_col(&self) -> u16 {
+        self.seed_to_string().len() as u16 + 3
+    }
+
+    /// Changes the health of the player out of its `lives`, displayed as hearts next to the score
+    /// (only if the player has more than one life).
+    pub fn set_health(&mut self, health: u16, lives: u16) {
+        self.health = health;
+        self.lives = lives;
+    }
+
+    /// Returns the health of the player as a `String` of full and empty hearts.
+    fn hearts_to_string(&self) -> String {
+        let full = self.health.min(self.lives) as usize;
+        let empty = self.lives as usize - full;
+        format!("{}{}", "♥".repeat(full), "♡".repeat(empty))
+    }
+
+    /// Changes the status line displayed below the seed (e.g. the replay progress).
+    pub fn set_status(&mut self, status: String) {
+        self.status = status;
+    }
+
+    /// Ruturns a reference to the HUD score.
+    pub fn score(&self) -> &Score {
+        &self.score
+    }
+
+    /// Ruturns a mutable reference to the HUD score.
+    pub fn score_mut(&mut self) -> &mut Score {
+        &mut self.score
+    }
+
+    /// Ruturns a reference to the HUD splash screen.
+    pub fn splash(&self) -> &Splash {
+        &self.splash
+    }
+
+    /// Ruturns a mutable reference to the HUD splash screen.
+    pub fn splash_mut(&mut self) -> &mut Splash {
+        &mut self.splash
+    }
+
+    /// Ruturns a reference to the HUD controls screen.
+    pub fn controls(&self) -> &Controls {
+        &self.controls
+    }
+
+    /// Ruturns a mutable reference to the HUD controls screen.
+    pub fn controls_mut(&mut self) -> &mut Controls {
+        &mut self.controls
+    }
+
+    /// Ruturns a reference to the HUD settings screen.
+    pub fn preferences(&self) -> &Preferences {
+        &self.preferences
+    }
+
+    /// Ruturns a mutable reference to the HUD settings screen.
+    pub fn preferences_mut(&mut self) -> &mut Preferences {
+        &mut self.preferences
+    }
+}
+
+impl Render for Hud {
+    fn render(&self, out: &mut crate::graphics::TermOut) {
+        self.score.render(out);
+        write!(out, "{}{}", Goto(1, 3), self.seed_to_string()).unwrap();
+        if self.timer.is_some() {
+            let timer = self.timer_to_string();
+            write!(out, "{}{}", Goto(self.timer_col(), 3), timer).unwrap();
+        }
+        write!(out, "{}{}", Goto(1, 4), self.status).unwrap();
+        if self.lives > 1 {
+            let col = self.score.curr_to_string().len() as u16 + 3;
+            write!(
+                out,
+                "{}{}{}{}",
+                Goto(col, 2),
+                Fg(Red),
+                self.hearts_to_string(),
+                Fg(Reset)
+            )
+            .unwrap();
+        }
+        if !self.toast.is_empty() {
+            let col = self.toast_col();
+            write!(
+                out,
+                "{}{}{}{}",
+                Goto(col, 1),
+                Fg(Yellow),
+                self.toast,
+                Fg(Reset)
+            )
+            .unwrap();
+        }
+        for (row, line) in (5..).zip(&self.alert) {
+            write!(
+                out,
+                "{}{}{}{}{}{}",
+                Goto(1, row),
+                Bg(Red),
+                Fg(LightWhite),
+                line,
+                Fg(Reset),
+                Bg(Reset)
+            )
+            .unwrap();
+        }
+    }
+
+    fn erase(&self, out: &mut crate::graphics::TermOut) {
+        let toast_empty = " ".repeat(self.toast.chars().count());
+        write!(out, "{}{}", Goto(self.toast_col(), 1), toast_empty).unwrap();
+        let seed_empty = " ".repeat(self.seed_to_string().len());
+        write!(out, "{}{}", Goto(1, 3), seed_empty).unwrap();
+        // the timer can be one digit shorter than when it was rendered
+        let timer_empty = " ".repeat(self.timer_to_string().len() + 1);
+        write!(out, "{}{}", Goto(self.timer_col(), 3), timer_empty).unwrap();
+        let status_empty = " ".repeat(self.status.chars().count());
+        write!(out, "{}{}", Goto(1, 4), status_empty).unwrap();
+        if self.lives > 1 {
+            let col = self.score.curr_to_string().len() as u16 + 3;
+            write!(out, "{}{}", Goto(col, 2), " ".repeat(self.lives as usize)).unwrap();
+        }
+        for (row, line) in (5..).zip(&self.alert) {
+            write!(out, "{}{}", Goto(1, row), " ".repeat(line.chars().count())).unwrap();
+        }
+        self.score.erase(out);
+    }
+}
+
+impl DynComp for Hud {
+    /// Updates the score and the toast.
+    fn update(&mut self) {
+        self.score.update();
+        self.toast_steps = self.toast_steps.saturating_sub(1);
+        if self.toast_steps == 0 {
+            self.toast.clear();
+        }
+    }
+
+    /// Resets the score and hides the toast.
+    fn reset(&mut self) {
+        self.score.reset();
+        self.toast.clear();
+        self.toast_steps = 0;
+    }
+}
+
+impl Subscriber for Hud {
+    /// Adds the bonuses to the score and toasts the score milestones, the broken record and the
+    /// campaign levels.
+    fn notify(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::Bonus(points) => self.score.add(*points),
+            GameEvent::ScoreMilestone(score) => self.toast(format!("{} points!", score)),
+            GameEvent::RecordBroken { .. } => self.toast(Splash::NEW_RECORD.to_string()),
+            GameEvent::LevelReached(level) => self.toast(format!("Level {}!", level)),
+            _ => (),
+        }
+    }
+}
+
+/// Tracks the current and the best user score (which is displayed in the top-left corner) .
+#[derive(Debug)]
+pub struct Score {
+    current: u32,
+    record: u32,
+}
+
+impl Score {
+    // current score label
+    const CURR_LABEL: &'static str = "Score";
+    // record score label
+    const REC_LABEL: &'static str = "Record";
+
+    /// Returns a new instace of `Score`.
+    pub fn new() -> Self {
+        Score {
+            current: 0,
+            record: 0,
+        }
+    }
+
+    /// Returns the current score.
+    ///
+    /// The score is updated each time `DynComp` trait methods are called. [Read more]
+    ///
+    /// [Read more]: #impl-DynComp-for-Score
+    pub fn current(&self) -> u32 {
+        self.current
+    }
+
+    /// Returns the best score.
+    ///
+    /// The best score is updated each time `DynComp` trait methods are called. [Read more]
+    ///
+    /// [Read more]: #method.reset
+    pub fn record(&self) -> u32 {
+        self.record
+    }
+
+    /// Changes the best score (e.g. to the one saved of the previous sessions).
+    pub fn set_record(&mut self, record: u32) {
+        self.record = record;
+    }
+
+    /// Adds `points` to the current score.
+    pub fn add(&mut self, points: u32) {
+        self.current = self.current.saturating_add(points);
+    }
+
+    /// Discards the current score, so it doesn't count for the best score.
+    pub fn discard(&mut self) {
+        self.current = 0;
+    }
+
+    /// Returns the current score as a formated `String`.
+    pub fn curr_to_string(&self) -> String {
+        format!("{}: {:0>10}", Self::CURR_LABEL, self.current)
+    }
+
+    /// Returns the current score as a formated `String`.
+    pub fn best_to_string(&self) -> String {
+        format!("{}: {:0>10}", Self::REC_LABEL, self.record)
+    }
+}
+
+impl Default for Score {
+    fn default() -> Self {
+        Score::new()
+    }
+}
+
+impl DynComp for Score {
+    /// Increases the current score by 1.
+    fn update(&mut self) {
+        self.current += 1;
+    }
+
+    /// Sets the current score to 0 and checks if the best score has changed.
+    fn reset(&mut self) {
+        self.record = self.record.max(self.current);
+        self.current = 0;
+    }
+}
+
+impl Render for Score {
+    fn render(&self, out: &mut crate::graphics::TermOut) {
+        write!(out, "{}{}", Goto(1, 1), self.best_to_string()).unwrap();
+        write!(out, "{}{}", Goto(1, 2), self.curr_to_string()).unwrap();
+    }
+
+    fn erase(&self, out: &mut crate::graphics::TermOut) {
+        let curr_empty = " ".repeat(self.curr_to_string().len());
+        let best_empty = " ".repeat(self.best_to_string().len());
+        write!(out, "{}{}", Goto(1, 1), curr_empty).unwrap();
+        write!(out, "{}{}", Goto(1, 2), best_empty).unwrap();
+    }
+}
+
+/// Creates a new splash screen object.
+fn splash_obj<C: Color>(msg: &str, color: C, size: &Size) -> Obj {
+    let h_center = (size.width / 2) + 1;
+    let v_center = size.height / 2;
+    let width = msg.chars().count() as u16;
+    let col = h_center.checked_sub(width / 2).unwrap_or(1);
+    let center_pos = Pos { col, row: v_center };
+
+    let chars = msg.chars().collect();
+    let sprite = Sprite::new(chars, width);
+    Obj::new(center_pos, sprite, &Fg(color))
+}
+
+/// The results of a run, displayed by the game over screen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Summary {
+    /// Final score.
+    pub score: u32,
+    /// Best score before the run.
+    pub record: u32,
+    /// How many world steps the player ran.
+    pub distance: u32,
+    /// How many times the player jumped.
+    pub jumps: u32,
+    /// Name of the difficulty of the run.
+    pub difficulty: String,
+    /// Name of the mode of the run.
+    pub mode: String,
+    /// Whether the run counts for the record (see [`Mode::is_ranked`]).
+    ///
+    /// [`Mode::is_ranked`]: crate::game::Mode::is_ranked
+    pub ranked: bool,
+}
+
+impl Summary {
+    /// Returns `true` if the run broke the record.
+    pub fn is_record(&self) -> bool {
+        self.ranked && self.score > self.record
+    }
+
+    /// Returns the lines of the results.
+    fn lines(&self) -> Vec<String> {
+        let record = if self.ranked {
+            self.record.max(self.score)
+        } else {
+            self.record
+        };
+        vec![
+            format!("{:<12}{:0>10}", "Score:", self.score),
+            format!("{:<12}{:0>10}", "Record:", record),
+            format!("{:<12}{}", "Distance:", self.distance),
+            format!("{:<12}{}", "Jumps:", self.jumps),
+            format!("{:<12}{}", "Difficulty:", self.difficulty),
+            format!("{:<12}{}", "Mode:", self.mode),
+        ]
+    }
+}
+
+/// Manages "title", "pause" and "game over" splash-screens with vertically and horizontally
+/// centered text.
+#[derive(Debug)]
+pub struct Splash {
+    title: Obj,
+    pause: Obj,
+    game_over: Obj,
+    size: Size,
+}
+
+impl Splash {
+    // game over text
+    const GAME_OVER: &'static str = "Game Over";
+    // game over text when the record is broken
+    const NEW_RECORD: &'static str = "New record!";
+    // game over hint
+    const PLAY_AGAIN: &'static str = "Press any key to play again.";
+
+    /// Returns a new instance of `Splash`.
+    ///
+    /// `tcolor` and `pcolor` refers to the `title` and the `pause_msg` foreground colors on the
+    /// splash screen, respectively.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use termion::color;
+    /// use crabbit::graphics::Size;
+    /// use crabbit::components::hud::Splash;
+    ///
+    /// let screen= Size {
+    ///     width: 300,
+    ///     height: 400
+    /// };
+    ///
+    /// let title = "CRABBIT";
+    /// let title_color = color::Blue;
+    ///
+    /// let pause_msg = "Paused";
+    /// let pause_msg_color = color::Magenta;
+    ///
+    /// let splash: Splash = Splash::new(&screen, title, title_color, pause_msg, pause_msg_color);
+    /// ```
+    pub fn new<T, P>(size: &Size, title: &str, tcolor: T, pause_msg: &str, pcolor: P) -> Self
+    where
+        T: Color,
+        P: Color,
+    {
+        let title = splash_obj(title, tcolor, size);
+        let pause = splash_obj(pause_msg, pcolor, size);
+        let game_over = splash_obj(Self::GAME_OVER, Red, size);
+
+        Splash {
+            title,
+            pause,
+            game_over,
+            size: size.clone(),
+        }
+    }
+
+    /// Renders the title screen.
+    pub fn render_title(&self, out: &mut crate::graphics::TermOut) {
+        self.title.render(out);
+    }
+
+    /// Renders the pause screen (the pause menu is rendered below it by the scene).
+    pub fn render_pause(&self, out: &mut crate::graphics::TermOut) {
+        self.pause.render(out);
+    }
+
+    /// Renders the game over screen with the `summary` of the run centered below it.
+    pub fn render_game_over(&self, out: &mut crate::graphics::TermOut, summary: &Summary) {
+        self.game_over.render(out);
+
+        let mut lines = summary.lines();
+        let width = lines.iter().map(|line| line.len()).max().unwrap_or(0) as u16;
+        if summary.is_record() {
+            lines.push(format!("{}{}{}", Fg(Yellow), Self::NEW_RECORD, Fg(Reset)));
+        }
+        lines.push(String::new());
+        lines.push(Self::PLAY_AGAIN.to_string());
+
+        let col = ((self.size.width / 2) + 1).saturating_sub(width / 2).max(1);
+        let row = self.size.height / 2 + 2;
+        for (row, line) in (row..).zip(lines) {
+            write!(out, "{}{}", Goto(col, row), line).unwrap();
+        }
+    }
+
+    /// Renders `lines` of text (e.g. the statistics) centered on the screen.
+    pub fn render_lines(&self, out: &mut crate::graphics::TermOut, lines: &[String]) {
+        let width = lines.iter().map(|line| line.len()).max().unwrap_or(0) as u16;
+        let row = (self.size.height / 2).saturating_sub(lines.len() as u16 / 2);
+        let col = ((self.size.width / 2) + 1).saturating_sub(width / 2).max(1);
+        for (row, line) in (row.max(1)..).zip(lines) {
+            write!(out, "{}{}", Goto(col, row), line).unwrap();
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use termion::{cursor::Goto, event::Key, style};
+
+use crate::{
+    game::Difficulty,
+    graphics::{Pos, Render, Size, TermOut},
+    settings::Settings,
+};
+
+/// An option of the preferences screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pref {
+    Difficulty,
+    Lives,
+    Mouse,
+    Kitty,
+}
+
+impl Pref {
+    // the options, in the order that they are displayed
+    const ALL: [Pref; 4] = [Pref::Difficulty, Pref::Lives, Pref::Mouse, Pref::Kitty];
+
+    /// Returns the label of the option.
+    fn label(&self) -> &'static str {
+        match self {
+            Pref::Difficulty => "Difficulty",
+            Pref::Lives => "Lives",
+            Pref::Mouse => "Mouse",
+            Pref::Kitty => "Kitty keyboard",
+        }
+    }
+}
+
+/// A screen that lets the user change the settings (see [`Settings`]) other than the key bindings
+/// (see [`Controls`](super::Controls)).
+///
+/// The options are selected with the arrow keys up and down and changed with the arrow keys left
+/// and right (or `Enter`). `Esc` leaves the screen.
+#[derive(Debug)]
+pub struct Preferences {
+    settings: Settings,
+    changed: bool,
+    selected: usize,
+    open: bool,
+    message: String,
+    pos: Pos,
+}
+
+impl Preferences {
+    // screen title
+    const TITLE: &'static str = "Settings";
+    // usage help
+    const HELP: &'static str = "up/down: select  left/right: change  esc: back";
+    // when the options apply
+    const NOTE: &'static str =
+        "Difficulty and lives apply to the next run, the rest to the next launch.";
+    // screen width (in characters)
+    const WIDTH: u16 = 76;
+    // screen height: title, blank line, options, blank line, help and note.
+    const HEIGHT: u16 = Pref::ALL.len() as u16 + 5;
+    // most lives that the player can have
+    const MAX_LIVES: u16 = 9;
+
+    /// Returns a new (closed) instance of `Preferences` centered on a screen of the given `size`.
+    pub fn new(size: &Size) -> Self {
+        let col = (size.width.saturating_sub(Self::WIDTH) / 2) + 1;
+        let row = (size.height.saturating_sub(Self::HEIGHT) / 2).max(1);
+
+        Preferences {
+            settings: Settings::default(),
+            changed: false,
+            selected: 0,
+            open: false,
+            message: String::new(),
+            pos: Pos { col, row },
+        }
+    }
+
+    /// Opens the screen to edit `settings`.
+    pub fn open(&mut self, settings: Settings) {
+        self.settings = settings;
+        self.changed = false;
+        self.selected = 0;
+        self.open = true;
+        self.message.clear();
+    }
+
+    /// Returns `true` if the screen is open and `false` otherwise.
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Shows `msg` at the bottom of the screen, instead of when the settings apply.
+    pub fn set_message(&mut self, msg: String) {
+        self.message = msg;
+    }
+
+    /// Handles a key pressed while the screen is open.
+    ///
+    /// When the screen is closed returns the edited settings if they have changed.
+    pub fn handle(&mut self, key: Key) -> Option<Settings> {
+        if !self.open {
+            return None;
+        }
+
+        match key {
+            Key::Up => self.selected = self.selected.saturating_sub(1),
+            Key::Down => self.selected = (self.selected + 1).min(Pref::ALL.len() - 1),
+            Key::Left => self.change(false),
+            Key::Right | Key::Char('\n') => self.change(true),
+            Key::Esc => {
+                self.open = false;
+                if self.changed {
+                    return Some(self.settings.clone());
+                }
+            }
+            _ => (),
+        }
+        None
+    }
+
+    /// Changes the selected option to its next (or previous, if not `forward`) value.
+    fn change(&mut self, forward: bool) {
+        let settings = &mut self.settings;
+        match Pref::ALL[self.selected] {
+            Pref::Difficulty => {
+                let all = Difficulty::ALL;
+                let i = all
+                    .iter()
+                    .position(|d| *d == settings.difficulty)
+                    .unwrap_or(0);
+                let next = if forward { i + 1 } else { i + all.len() - 1 };
+                settings.difficulty = all[next % all.len()];
+            }
+            Pref::Lives if forward => settings.lives = (settings.lives + 1).min(Self::MAX_LIVES),
+            Pref::Lives => settings.lives = settings.lives.saturating_sub(1).max(1),
+            Pref::Mouse => settings.mouse = !settings.mouse,
+            Pref::Kitty => settings.kitty_keyboard = !settings.kitty_keyboard,
+        }
+        self.changed = true;
+    }
+
+    /// Returns the value of an option.
+    fn value(&self, pref: Pref) -> String {
+        let on_off = |on: bool| if on { "on" } else { "off" }.to_string();
+        match pref {
+            Pref::Difficulty => self.settings.difficulty.name().to_string(),
+            Pref::Lives => self.settings.lives.to_string(),
+            Pref::Mouse => on_off(self.settings.mouse),
+            Pref::Kitty => on_off(self.settings.kitty_keyboard),
+        }
+    }
+
+    /// Returns the lines of the screen.
+    fn lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("{:^1$}", Self::TITLE, Self::WIDTH as usize),
+            String::new(),
+        ];
+        for (i, pref) in Pref::ALL.into_iter().enumerate() {
+            let cursor = if i == self.selected { '>' } else { ' ' };
+            lines.push(format!(
+                " {} {:<16}< {} >",
+                cursor,
+                pref.label(),
+                self.value(pref)
+            ));
+        }
+        lines.push(String::new());
+        lines.push(format!(" {}", Self::HELP));
+        if self.message.is_empty() {
+            lines.push(format!(" {}", Self::NOTE));
+        } else {
+            lines.push(format!(" {}", self.message));
+        }
+        lines
+    }
+}
+
+impl Render for Preferences {
+    fn render(&self, out: &mut TermOut) {
+        if !self.open {
+            return;
+        }
+
+        let width = Self::WIDTH as usize;
+        for (row, line) in (self.pos.row..).zip(self.lines()) {
+            let line: String = line.chars().take(width).collect();
+            let selected = row == self.pos.row + 2 + self.selected as u16;
+            let invert = if selected {
+                style::Invert.to_string()
+            } else {
+                String::new()
+            };
+            write!(
+                out,
+                "{}{}{:<w$}{}",
+                Goto(self.pos.col, row),
+                invert,
+                line,
+                style::Reset,
+                w = width
+            )
+            .unwrap();
+        }
+    }
+
+    fn erase(&self, out: &mut TermOut) {
+        if !self.open {
+            return;
+        }
+
+        let overwrite = " ".repeat(Self::WIDTH as usize);
+        for row in self.pos.row..(self.pos.row + Self::HEIGHT) {
+            write!(out, "{}{}", Goto(self.pos.col, row), overwrite).unwrap();
+        }
+    }
+}
@@ -10,6 +10,14 @@ pub mod stage;
 pub use player::Player;
 pub use stage::Stage;
 
+/// Random number generator of the components that generate random content.
+///
+/// It is always created from a seed (drawn from the [`Game`] generator), so the same seed always
+/// generates the same content.
+///
+/// [`Game`]: crate::game::Game
+pub type GameRng = rand::rngs::StdRng;
+
 /// Define behavior of *"Dynamic Components"* (components that moves).
 pub trait DynComp {
     /// Update component (go to the next frame).
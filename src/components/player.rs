@@ -27,9 +27,33 @@ pub struct Player {
     pub obj: Obj,
     default_pos: Pos,
     moves: VecDeque<Move>,
+    boost: bool,
+    boosted: u16,
+    icon: char,
+    dying: u16,
+    invulnerable: u16,
 }
 
 impl Player {
+    /// Maximum extra height of a boosted jump (see [`Player::boost`]).
+    pub const MAX_BOOST: u16 = 2;
+    // death sequence, one character per update (`None` for the player icon): the player flashes
+    // and then explodes.
+    const DEATH: [Option<char>; 12] = [
+        Some('X'),
+        Some('X'),
+        None,
+        None,
+        Some('X'),
+        Some('X'),
+        None,
+        None,
+        Some('*'),
+        Some('*'),
+        Some('+'),
+        Some('.'),
+    ];
+
     /// Creates a new instance of `Player`.
     pub fn new<C: Color>(icon: char, color: C, floor: u16) -> Self {
         let sprite = Sprite::new(vec![icon], 1);
@@ -39,9 +63,14 @@ impl Player {
         };
         Player {
             state: PlayerState::Running,
-            obj: Obj::new(pos.clone(), sprite, &Fg(color)),
+            obj: Obj::new(pos, sprite, &Fg(color)),
             default_pos: pos,
             moves: VecDeque::new(),
+            boost: false,
+            boosted: 0,
+            icon,
+            dying: 0,
+            invulnerable: 0,
         }
     }
 
@@ -63,6 +92,7 @@ impl Player {
     pub fn jump(&mut self, height: u16) {
         if let PlayerState::Running = self.state {
             self.state = PlayerState::Jumping;
+            self.boosted = 0;
             for _ in 0..height {
                 self.up(1);
             }
@@ -73,9 +103,64 @@ impl Player {
         }
     }
 
-    /// Kills player (change state).
+    /// Makes the jumps go higher while `on` is `true`.
+    ///
+    /// When a jump reaches its top while boosted, it keeps going up (at most `MAX_BOOST` extra
+    /// rows), so holding the jump key makes the player jump higher.
+    pub fn boost(&mut self, on: bool) {
+        self.boost = on;
+    }
+
+    /// Returns how many rows the player is above the ground.
+    pub fn height(&self) -> u16 {
+        self.default_pos.row.saturating_sub(self.obj.pos.row)
+    }
+
+    /// Returns the row of the player when it is on the ground.
+    pub fn ground(&self) -> u16 {
+        self.default_pos.row
+    }
+
+    /// Makes the player invulnerable (blinking) for the next `ticks` updates, after being hurt.
+    pub fn hurt(&mut self, ticks: u16) {
+        self.invulnerable = ticks;
+    }
+
+    /// Returns `true` if the player can't be hurt (see [`Player::hurt`]).
+    pub fn is_invulnerable(&self) -> bool {
+        self.invulnerable > 0
+    }
+
+    /// Kills player (change state), starting its death sequence (see [`Player::is_dead`]).
     pub fn kill(&mut self) {
         self.state = PlayerState::Killed;
+        self.dying = 0;
+        self.moves.clear();
+        self.obj.sprite.set_ascii(vec![self.death_frame()]);
+    }
+
+    /// Returns `true` if the player was killed and its death sequence is over.
+    ///
+    /// While killed, each update advances the death sequence instead of moving the player.
+    pub fn is_dead(&self) -> bool {
+        matches!(self.state, PlayerState::Killed) && self.dying as usize >= Self::DEATH.len()
+    }
+
+    /// Displays the player with the icon and the color of `look`, keeping its state.
+    pub fn restyle(&mut self, look: &Player) {
+        self.icon = look.icon;
+        self.obj.color.clone_from(&look.obj.color);
+        if !matches!(self.state, PlayerState::Killed) {
+            self.obj.sprite.set_ascii(vec![self.icon]);
+        }
+    }
+
+    /// Returns the character displayed at the given update of the death sequence.
+    fn death_frame(&self) -> char {
+        match Self::DEATH.get(self.dying as usize) {
+            Some(frame) => frame.unwrap_or(self.icon),
+            None => ' ',
+        }
     }
 }
 
@@ -91,6 +176,25 @@ impl Render for Player {
 
 impl DynComp for Player {
     fn update(&mut self) {
+        if let PlayerState::Killed = self.state {
+            self.dying = (self.dying + 1).min(Self::DEATH.len() as u16);
+            self.obj.sprite.set_ascii(vec![self.death_frame()]);
+            return;
+        }
+
+        // the player blinks while invulnerable.
+        self.invulnerable = self.invulnerable.saturating_sub(1);
+        let hidden = self.is_invulnerable() && (self.invulnerable / 2) % 2 == 1;
+        let icon = if hidden { ' ' } else { self.icon };
+        self.obj.sprite.set_ascii(vec![icon]);
+
+        let at_top = matches!(self.moves.front(), Some(Move::Stop));
+        if self.boost && at_top && self.boosted < Self::MAX_BOOST && self.obj.pos.row > 1 {
+            self.moves.push_front(Move::Up(1));
+            self.moves.push_back(Move::Down(1));
+            self.boosted += 1;
+        }
+
         if let Some(mv) = self.moves.pop_front() {
             match mv {
                 Move::Up(amount) if self.obj.pos.row > amount => self.obj.pos.row -= amount,
@@ -105,8 +209,12 @@ impl DynComp for Player {
     }
 
     fn reset(&mut self) {
-        self.obj.pos = self.default_pos.clone();
+        self.obj.pos = self.default_pos;
         self.moves.clear();
         self.state = PlayerState::Running;
+        self.boost = false;
+        self.dying = 0;
+        self.invulnerable = 0;
+        self.obj.sprite.set_ascii(vec![self.icon]);
     }
 }
@@ -0,0 +1,371 @@
+use rand::Rng;
+use serde::Deserialize;
+use std::collections::VecDeque;
+use termion::color::{Color, Fg};
+
+use crate::{
+    components::{DynComp, GameRng},
+    graphics::{
+        object::{Obj, Sprite},
+        Pos, Render, Size, TermOut,
+    },
+};
+
+pub mod terrain;
+pub mod tiles;
+pub use terrain::Terrain;
+pub use tiles::Tiles;
+
+/// Where a [`Layer`] is placed vertically on the [`Stage`].
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Anchor {
+    /// On top of the previously stacked layers (starting from the bottom of the screen).
+    #[default]
+    Stacked,
+    /// The last row of the layer is the last row of the screen.
+    Bottom,
+    /// The first row of the layer is the first row of the screen.
+    Top,
+    /// The first row of the layer is the given row.
+    Row(u16),
+    /// The last row of the layer is right above the highest of the floor layer and the layers
+    /// anchored to the bottom (the bottom of the screen if there are none), so it never overlaps
+    /// them. The floor layer itself can't be anchored to the floor.
+    Floor,
+}
+
+/// An endless sequence of columns that a [`Layer`] scrolls through.
+pub trait Strip {
+    /// Returns the height (number of rows) of each column.
+    fn height(&self) -> u16;
+    /// Returns the next column of the strip (from top to bottom).
+    fn next_col(&mut self) -> Vec<char>;
+    /// Goes back to the first column of the strip.
+    fn rewind(&mut self);
+    /// Changes the seed of a randomly generated strip and goes back to its first column. Strips
+    /// that aren't random don't need to implement it.
+    fn reseed(&mut self, _seed: u64) {}
+}
+
+/// A [`Strip`] that repeats the same sprite forever.
+pub struct Tiled {
+    sprite: Sprite,
+    col: u16,
+}
+
+impl Tiled {
+    /// Returns a new instance of `Tiled`.
+    pub fn new(sprite: Sprite) -> Self {
+        Tiled { sprite, col: 0 }
+    }
+}
+
+impl Strip for Tiled {
+    fn height(&self) -> u16 {
+        self.sprite.size().1
+    }
+
+    fn next_col(&mut self) -> Vec<char> {
+        let (width, height) = self.sprite.size();
+        let col = (0..height)
+            .map(|i| {
+                *self
+                    .sprite
+                    .get(i, self.col)
+                    .expect("Sprite element not found!")
+            })
+            .collect();
+        self.col = (self.col + 1) % width;
+        col
+    }
+
+    fn rewind(&mut self) {
+        self.col = 0;
+    }
+}
+
+/// A [`Stage`] layer.
+pub struct Layer {
+    pub size: Size,
+    strip: Box<dyn Strip>,
+    window: VecDeque<Vec<char>>,
+    shift: u16,
+    anchor: Anchor,
+    v_offset: i16,
+}
+
+impl Layer {
+    /// Returns a new builder of a layer that tiles `sprite`.
+    pub fn builder(width: u16, sprite: Sprite) -> LayerBuild<Tiled> {
+        Self::streamed(width, Tiled::new(sprite))
+    }
+
+    /// Returns a new builder of a layer that scrolls through the columns of `strip`.
+    pub fn streamed<S: Strip>(width: u16, strip: S) -> LayerBuild<S> {
+        LayerBuild {
+            width,
+            strip,
+            shift: None,
+            anchor: Anchor::default(),
+            v_offset: 0,
+        }
+    }
+
+    /// Returns `true` if the layer doesn't move and `false` otherwise.
+    pub fn is_static(&self) -> bool {
+        self.shift == 0
+    }
+
+    /// Returns a visual ascii matrix representation of the layer.
+    pub fn ascii_matrix(&self) -> Vec<char> {
+        let (width, height) = (self.size.width as usize, self.size.height as usize);
+        let mut ascii_matrix = Vec::with_capacity(width * height);
+
+        for i in 0..height {
+            ascii_matrix.extend(self.window.iter().map(|col| col[i]));
+        }
+        ascii_matrix
+    }
+
+    /// Returns the sprite representation of the layer.
+    pub fn to_sprite(&self) -> Sprite {
+        let ascii_matrix = self.ascii_matrix();
+        Sprite::new(ascii_matrix, self.size.width)
+    }
+
+    /// Fills the visible window with the first columns of the strip.
+    fn fill(&mut self) {
+        self.window.clear();
+        for _ in 0..self.size.width {
+            self.window.push_back(self.strip.next_col());
+        }
+    }
+}
+
+impl DynComp for Layer {
+    fn update(&mut self) {
+        for _ in 0..self.shift {
+            self.window.pop_front();
+            self.window.push_back(self.strip.next_col());
+        }
+    }
+
+    fn reset(&mut self) {
+        self.strip.rewind();
+        self.fill();
+    }
+}
+
+/// Builds a new stage `Layer`.
+pub struct LayerBuild<S: Strip> {
+    width: u16,
+    strip: S,
+    shift: Option<u16>,
+    anchor: Anchor,
+    v_offset: i16,
+}
+
+impl<S: Strip + 'static> LayerBuild<S> {
+    /// Changes the step that the layer moves.
+    pub fn shift(mut self, step: u16) -> Self {
+        self.shift = Some(step);
+        self
+    }
+
+    /// Changes where the layer is placed on the stage.
+    pub fn anchor(mut self, anchor: Anchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    /// Moves the layer `rows` down (or up, if negative) from its anchored position.
+    pub fn v_offset(mut self, rows: i16) -> Self {
+        self.v_offset = rows;
+        self
+    }
+
+    /// Builds a new `Layer`.
+    pub fn build(self) -> Layer {
+        let size = Size {
+            width: self.width,
+            height: self.strip.height(),
+        };
+
+        let mut layer = Layer {
+            size,
+            strip: Box::new(self.strip),
+            window: VecDeque::with_capacity(self.width as usize),
+            shift: self.shift.unwrap_or(1),
+            anchor: self.anchor,
+            v_offset: self.v_offset,
+        };
+        layer.fill();
+        layer
+    }
+}
+
+impl LayerBuild<Tiled> {
+    /// Add gap between the sprites.
+    pub fn gap(mut self, size: usize) -> Self {
+        self.strip.sprite.stretch(size, ' ');
+        self
+    }
+}
+
+/// A game stage (scenario).
+pub struct Stage {
+    pub size: Size,
+    pub floor: u16,
+    layers: Vec<Layer>,
+    objs: Vec<Obj>,
+    floor_layer: Option<usize>,
+}
+
+impl Stage {
+    /// Returns a new Stage instance.
+    pub fn new(width: u16, height: u16) -> Self {
+        Stage {
+            size: Size { width, height },
+            floor: 1,
+            layers: Vec::new(),
+            objs: Vec::new(),
+            floor_layer: None,
+        }
+    }
+
+    /// Push a new layer to the layer stack and returns its index.
+    pub fn push_layer<C: Color>(&mut self, layer: Layer, color: C) -> usize {
+        let sprite = layer.to_sprite();
+        let pos = Pos { col: 1, row: 1 };
+        let obj = Obj::new(pos, sprite, &Fg(color));
+
+        self.objs.push(obj);
+        self.layers.push(layer);
+        self.arrange();
+        self.layers.len() - 1
+    }
+
+    /// Sets the `Layer` with the index `layer` as the floor of the stage.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no layer with the given index, or if the layer is anchored to the floor
+    /// (see [`Anchor::Floor`]).
+    pub fn set_floor(&mut self, layer: usize) {
+        assert!(
+            layer < self.layers.len(),
+            "No stage layer at index {}.",
+            layer
+        );
+        assert!(
+            !matches!(self.layers[layer].anchor, Anchor::Floor),
+            "The floor layer can't be anchored to the floor."
+        );
+        self.floor_layer = Some(layer);
+        self.arrange();
+    }
+
+    /// Reseeds the randomly generated layers from the game `rng`.
+    pub fn reseed(&mut self, rng: &mut GameRng) {
+        for (layer, obj) in self.layers.iter_mut().zip(self.objs.iter_mut()) {
+            layer.strip.reseed(rng.gen());
+            layer.fill();
+            obj.sprite.set_ascii(layer.ascii_matrix());
+        }
+    }
+
+    /// Moves the background: the layers that end above the floor (all of them, if there is no
+    /// floor layer). The floor and the layers that reach it, where the game takes place, don't
+    /// move.
+    pub fn scroll_background(&mut self) {
+        let floor = self.floor_layer.map_or(u16::MAX, |_| self.floor);
+        for (layer, obj) in self.layers.iter_mut().zip(self.objs.iter_mut()) {
+            let above = obj.pos.row.saturating_add(layer.size.height) <= floor;
+            if above && !layer.is_static() {
+                layer.update();
+                obj.sprite.set_ascii(layer.ascii_matrix());
+            }
+        }
+    }
+
+    /// Updates the position of every layer according to its anchor.
+    ///
+    /// Layers anchored to the floor are placed last, since they depend on the final position of
+    /// the floor layer and of the layers anchored to the bottom (see [`Anchor::Floor`]), which are
+    /// never anchored to the floor.
+    fn arrange(&mut self) {
+        let mut stack_height = 0;
+        for (layer, obj) in self.layers.iter().zip(self.objs.iter_mut()) {
+            let height = layer.size.height;
+            let row = match layer.anchor {
+                Anchor::Stacked => {
+                    stack_height += height;
+                    self.size.height.saturating_sub(stack_height) + 1
+                }
+                Anchor::Bottom => self.size.height.saturating_sub(height) + 1,
+                Anchor::Top => 1,
+                Anchor::Row(row) => row,
+                Anchor::Floor => continue,
+            };
+            obj.pos.row = Self::offset_row(row, layer.v_offset);
+        }
+
+        self.floor = match self.floor_layer {
+            Some(i) => self.objs[i].pos.row,
+            None => 1,
+        };
+
+        // the highest first row of the floor layer and of the layers anchored to the bottom
+        let ground = self
+            .layers
+            .iter()
+            .zip(&self.objs)
+            .enumerate()
+            .filter(|(i, (layer, _))| {
+                Some(*i) == self.floor_layer || matches!(layer.anchor, Anchor::Bottom)
+            })
+            .map(|(_, (_, obj))| obj.pos.row)
+            .fold(self.size.height.saturating_add(1), u16::min);
+        for (layer, obj) in self.layers.iter().zip(self.objs.iter_mut()) {
+            if let Anchor::Floor = layer.anchor {
+                let row = ground.saturating_sub(layer.size.height);
+                obj.pos.row = Self::offset_row(row, layer.v_offset);
+            }
+        }
+    }
+
+    /// Moves `row` by `offset` rows without leaving the screen top.
+    fn offset_row(row: u16, offset: i16) -> u16 {
+        row.saturating_add_signed(offset).max(1)
+    }
+}
+
+impl Render for Stage {
+    fn render(&self, out: &mut TermOut) {
+        for obj in &self.objs {
+            obj.render(out);
+        }
+    }
+
+    fn erase(&self, out: &mut TermOut) {
+        for obj in &self.objs {
+            obj.erase(out);
+        }
+    }
+}
+
+impl DynComp for Stage {
+    fn update(&mut self) {
+        self.layers.iter_mut().enumerate().for_each(|(i, layer)| {
+            if !layer.is_static() {
+                layer.update();
+                self.objs[i].sprite.set_ascii(layer.ascii_matrix());
+            }
+        });
+    }
+
+    fn reset(&mut self) {
+        self.layers.iter_mut().for_each(|layer| layer.reset());
+    }
+}
@@ -0,0 +1,122 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use super::Strip;
+
+/// One dimensional value noise: random values at evenly spaced points smoothly interpolated.
+struct ValueNoise {
+    period: u16,
+    step: u16,
+    from: f64,
+    to: f64,
+}
+
+impl ValueNoise {
+    fn new(period: u16, rng: &mut StdRng) -> Self {
+        ValueNoise {
+            period,
+            step: 0,
+            from: rng.gen(),
+            to: rng.gen(),
+        }
+    }
+
+    /// Returns the next noise value (between 0 and 1).
+    fn next(&mut self, rng: &mut StdRng) -> f64 {
+        if self.step == self.period {
+            self.step = 0;
+            self.from = self.to;
+            self.to = rng.gen();
+        }
+
+        let t = self.step as f64 / self.period as f64;
+        let t = t * t * (3.0 - 2.0 * t);
+        self.step += 1;
+        self.from + (self.to - self.from) * t
+    }
+}
+
+/// A procedural [`Strip`] that draws an endless ridge line (like a mountain skyline) from seeded
+/// noise.
+///
+/// The ridge goes up or down at most one row per column, using `/` when it rises, `\` when it
+/// falls and `_` when it is flat. The same seed always generates the same ridge.
+pub struct Terrain {
+    height: u16,
+    seed: u64,
+    rng: StdRng,
+    hills: ValueNoise,
+    bumps: ValueNoise,
+    level: u16,
+}
+
+impl Terrain {
+    // columns between two hills
+    const HILLS_PERIOD: u16 = 11;
+    // columns between two bumps on a hill
+    const BUMPS_PERIOD: u16 = 4;
+    // how much the bumps change the hills height
+    const BUMPS_WEIGHT: f64 = 0.6;
+
+    /// Returns a new `Terrain` with `height` rows generated from `seed`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `height` is 0.
+    pub fn new(height: u16, seed: u64) -> Self {
+        assert!(height > 0, "A terrain must have at least one row.");
+        let mut rng = StdRng::seed_from_u64(seed);
+        let hills = ValueNoise::new(Self::HILLS_PERIOD, &mut rng);
+        let bumps = ValueNoise::new(Self::BUMPS_PERIOD, &mut rng);
+
+        Terrain {
+            height,
+            seed,
+            rng,
+            hills,
+            bumps,
+            level: 0,
+        }
+    }
+
+    /// Returns the level (rows above the bottom row) that the ridge is heading to.
+    fn target(&mut self) -> u16 {
+        let hills = self.hills.next(&mut self.rng);
+        let bumps = self.bumps.next(&mut self.rng) - 0.5;
+        let noise = (hills + bumps * Self::BUMPS_WEIGHT).clamp(0.0, 1.0);
+        (noise * (self.height - 1) as f64).round() as u16
+    }
+}
+
+impl Strip for Terrain {
+    fn height(&self) -> u16 {
+        self.height
+    }
+
+    fn next_col(&mut self) -> Vec<char> {
+        let mut col = vec![' '; self.height as usize];
+        let target = self.target();
+        let bottom = self.height - 1;
+
+        let (row, c) = if target > self.level {
+            let row = bottom - self.level;
+            self.level += 1;
+            (row, '/')
+        } else if target < self.level {
+            self.level -= 1;
+            (bottom - self.level, '\\')
+        } else {
+            (bottom - self.level, '_')
+        };
+
+        col[row as usize] = c;
+        col
+    }
+
+    fn rewind(&mut self) {
+        *self = Self::new(self.height, self.seed);
+    }
+
+    fn reseed(&mut self, seed: u64) {
+        *self = Self::new(self.height, seed);
+    }
+}
@@ -0,0 +1,133 @@
+use rand::{
+    distributions::{Distribution, WeightedIndex},
+    rngs::StdRng,
+    Rng, SeedableRng,
+};
+use std::ops::RangeInclusive;
+
+use super::Strip;
+use crate::graphics::object::Sprite;
+
+/// A [`Strip`] assembled from randomly chosen tiles (sprites) separated by gaps of random size.
+///
+/// Each tile is chosen according to its weight, so a tile with weight 2 shows up twice as often
+/// as a tile with weight 1. Tiles shorter than the tallest one are aligned to the bottom. The
+/// same seed always assembles the same strip.
+pub struct Tiles {
+    tiles: Vec<Sprite>,
+    weights: WeightedIndex<u32>,
+    gap: RangeInclusive<usize>,
+    height: u16,
+    seed: u64,
+    rng: StdRng,
+    current: usize,
+    col: u16,
+    gap_left: usize,
+}
+
+impl Tiles {
+    /// Returns a new `Tiles` builder that assembles the strip from `seed`.
+    pub fn builder(seed: u64) -> TilesBuild {
+        TilesBuild {
+            seed,
+            tiles: Vec::new(),
+            weights: Vec::new(),
+            gap: 0..=0,
+        }
+    }
+
+    /// Chooses the next tile and the size of the gap after it.
+    fn pick(&mut self) {
+        self.current = self.weights.sample(&mut self.rng);
+        self.gap_left = self.rng.gen_range(self.gap.clone());
+        self.col = 0;
+    }
+}
+
+impl Strip for Tiles {
+    fn height(&self) -> u16 {
+        self.height
+    }
+
+    fn next_col(&mut self) -> Vec<char> {
+        let tile = &self.tiles[self.current];
+        let (width, tile_height) = tile.size();
+
+        if self.col == width {
+            if self.gap_left > 0 {
+                self.gap_left -= 1;
+                return vec![' '; self.height as usize];
+            }
+            self.pick();
+            return self.next_col();
+        }
+
+        let padding = self.height - tile_height;
+        let col = (0..self.height)
+            .map(|i| match i.checked_sub(padding) {
+                Some(i) => *tile.get(i, self.col).expect("Sprite element not found!"),
+                None => ' ',
+            })
+            .collect();
+        self.col += 1;
+        col
+    }
+
+    fn rewind(&mut self) {
+        self.rng = StdRng::seed_from_u64(self.seed);
+        self.pick();
+    }
+
+    fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rewind();
+    }
+}
+
+/// Builds a new [`Tiles`] strip.
+pub struct TilesBuild {
+    seed: u64,
+    tiles: Vec<Sprite>,
+    weights: Vec<u32>,
+    gap: RangeInclusive<usize>,
+}
+
+impl TilesBuild {
+    /// Adds a tile that is chosen proportionally to `weight`.
+    pub fn tile(mut self, sprite: Sprite, weight: u32) -> Self {
+        self.tiles.push(sprite);
+        self.weights.push(weight);
+        self
+    }
+
+    /// Changes the range of sizes of the gap between two tiles.
+    pub fn gap(mut self, sizes: RangeInclusive<usize>) -> Self {
+        self.gap = sizes;
+        self
+    }
+
+    /// Builds a new `Tiles` strip.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no tile was added, if every weight is 0 or if the gap range is empty.
+    pub fn build(self) -> Tiles {
+        assert!(!self.gap.is_empty(), "Empty gap range for tiles.");
+        let weights = WeightedIndex::new(&self.weights).expect("Invalid tile weights.");
+        let height = self.tiles.iter().map(|t| t.size().1).max().unwrap_or(0);
+
+        let mut tiles = Tiles {
+            tiles: self.tiles,
+            weights,
+            gap: self.gap,
+            height,
+            seed: self.seed,
+            rng: StdRng::seed_from_u64(self.seed),
+            current: 0,
+            col: 0,
+            gap_left: 0,
+        };
+        tiles.rewind();
+        tiles
+    }
+}
@@ -0,0 +1,613 @@
+//! Game configuration: the stage layers, the player, the enemies and the HUD, described in a TOML
+//! file so themed variants of the game don't need to be recompiled.
+//!
+//! The default configuration is the `game.toml` file at the root of the repository, built into
+//! the game (see [`Config::builtin`]). Sprite paths are relative to the directory of the
+//! configuration file (the current directory for the built-in one), and so are the paths of
+//! enemy scripts (see [`Scripted`]).
+//!
+//! # Example
+//!
+//! ```
+//! use crabbit::config::Config;
+//!
+//! let text = r"
+//! [stage]
+//! floor = 'ground'
+//!
+//! [[stage.layers]]
+//! name = 'ground'
+//! color = 'green'
+//! shift = 2
+//! terrain = 2
+//!
+//! [player]
+//! icon = '@'
+//! color = 'lightyellow'
+//!
+//! [[enemies]]
+//! kind = 'walls'
+//! icon = '#'
+//! shift = 2
+//!
+//! [hud]
+//! title = { text = 'Jump!', color = 'blue' }
+//! pause = { text = 'Paused', color = 93 }
+//! ";
+//! let config = Config::parse(text, "theme.toml", ".").unwrap();
+//! let game = config.build(80, 24).unwrap();
+//! assert_eq!(game.player().obj.sprite.get(0, 0), Some(&'@'));
+//!
+//! // every error tells what is wrong and where
+//! let text = text.replace("floor = 'ground'", "floor = 'grass'");
+//! let e = Config::parse(&text, "theme.toml", ".").err().unwrap();
+//! assert_eq!(e.to_string(), "theme.toml: stage.floor: no layer named `grass`");
+//! ```
+
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+use termion::color::AnsiValue;
+
+use crate::{
+    components::{
+        enemies::{Enemies, Script, Scripted, Walls},
+        hud::{Controls, Hud, Splash},
+        stage::{Anchor, Layer, LayerBuild, Stage, Strip, Terrain, Tiles},
+        Player,
+    },
+    game::Game,
+    graphics::{object::Sprite, Pos},
+};
+
+// the configuration built into the game
+const BUILTIN: &str = include_str!("../game.toml");
+
+/// A game configuration (see the [module docs](self)).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// The scenario.
+    pub stage: StageConfig,
+    /// The player.
+    pub player: PlayerConfig,
+    /// The enemies, spawned from the right edge of the screen.
+    #[serde(default)]
+    pub enemies: Vec<EnemyConfig>,
+    /// The texts of the HUD.
+    pub hud: HudConfig,
+    /// The sprites used by the configuration, by path (as written in the file).
+    #[serde(skip)]
+    sprites: HashMap<String, Sprite>,
+    /// The compiled enemy scripts, by index of the enemy.
+    #[serde(skip)]
+    scripts: HashMap<usize, Script>,
+    /// The configuration file (`None` for the built-in configuration).
+    #[serde(skip)]
+    source: Option<PathBuf>,
+    /// The path the errors of the configuration are reported at.
+    #[serde(skip)]
+    path: PathBuf,
+    /// The directory of the sprites and scripts.
+    #[serde(skip)]
+    dir: PathBuf,
+}
+
+/// The stage layers.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StageConfig {
+    /// Name of the layer on which the player runs.
+    pub floor: String,
+    /// The layers, stacked from the bottom of the screen (unless anchored elsewhere).
+    pub layers: Vec<LayerConfig>,
+}
+
+/// A stage layer. It has exactly one of `sprite`, `tiles` or `terrain`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LayerConfig {
+    /// Name of the layer (unique in the stage).
+    pub name: String,
+    /// Color of the layer.
+    #[serde(default)]
+    pub color: ColorCode,
+    /// How many columns the layer moves per world step: different layers moving at different
+    /// speeds make the parallax. A layer with 0 doesn't move.
+    #[serde(default = "LayerConfig::default_shift")]
+    pub shift: u16,
+    /// Where the layer is placed on the stage.
+    #[serde(default)]
+    pub anchor: Anchor,
+    /// How many rows the layer is moved down (or up, if negative) from its anchored position.
+    #[serde(default)]
+    pub v_offset: i16,
+    /// A sprite tiled along the layer.
+    pub sprite: Option<String>,
+    /// Sprites randomly placed along the layer.
+    pub tiles: Option<Vec<TileConfig>>,
+    /// Height of a randomly generated mountain ridge.
+    pub terrain: Option<u16>,
+    /// Columns between two sprites or tiles: a size for a sprite, a size or a range (`[min,
+    /// max]`) for tiles.
+    pub gap: Option<Gap>,
+}
+
+impl LayerConfig {
+    fn default_shift() -> u16 {
+        1
+    }
+
+    /// Builds the layer with the placement and movement of this one.
+    fn finish<S: Strip + 'static>(&self, build: LayerBuild<S>) -> Layer {
+        build
+            .shift(self.shift)
+            .anchor(self.anchor)
+            .v_offset(self.v_offset)
+            .build()
+    }
+}
+
+/// A tile of a layer.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TileConfig {
+    /// Path of the sprite.
+    pub sprite: String,
+    /// How often the tile is chosen, relative to the other tiles.
+    #[serde(default = "TileConfig::default_weight")]
+    pub weight: u32,
+}
+
+impl TileConfig {
+    fn default_weight() -> u32 {
+        1
+    }
+}
+
+/// Size of the gap between sprites.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(untagged)]
+pub enum Gap {
+    /// A fixed size.
+    Fixed(usize),
+    /// A random size in the range (inclusive).
+    Range(usize, usize),
+}
+
+/// The player.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PlayerConfig {
+    /// Character of the player.
+    pub icon: char,
+    /// Color of the player.
+    #[serde(default)]
+    pub color: ColorCode,
+}
+
+/// An enemy spawner, by kind.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase", deny_unknown_fields)]
+pub enum EnemyConfig {
+    /// Walls standing on the floor (see [`Walls`]).
+    Walls {
+        /// Character of the walls.
+        icon: char,
+        /// Color of the walls.
+        #[serde(default = "ColorCode::red")]
+        color: ColorCode,
+        /// How many columns the walls move per world step.
+        shift: u16,
+        /// Where the walls appear.
+        #[serde(default)]
+        spawn: Spawn,
+    },
+    /// An enemy defined by a script (see [`Scripted`]).
+    Script {
+        /// Path of the script.
+        script: String,
+        /// Name of the enemy [default: the file name of the script, without extension].
+        name: Option<String>,
+        /// Color of the objects spawned by the script.
+        #[serde(default = "ColorCode::red")]
+        color: ColorCode,
+        /// How many columns the objects move per world step.
+        shift: u16,
+        /// Where the objects appear.
+        #[serde(default)]
+        spawn: Spawn,
+    },
+}
+
+impl EnemyConfig {
+    /// Returns how many columns the enemy moves per world step.
+    fn shift(&self) -> u16 {
+        match self {
+            Self::Walls { shift, .. } | Self::Script { shift, .. } => *shift,
+        }
+    }
+
+    /// Returns where the enemy appears.
+    fn spawn(&self) -> Spawn {
+        match self {
+            Self::Walls { spawn, .. } | Self::Script { spawn, .. } => *spawn,
+        }
+    }
+}
+
+/// Where an enemy appears, relative to the right edge of the screen and to the floor.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Spawn {
+    /// Columns to the left of the right edge of the screen.
+    pub from_right: u16,
+    /// Rows above the floor.
+    pub above_floor: u16,
+}
+
+/// The texts of the HUD.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HudConfig {
+    /// Message of the title screen.
+    pub title: TextConfig,
+    /// Message of the pause screen.
+    pub pause: TextConfig,
+}
+
+/// A colored text.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TextConfig {
+    /// The text.
+    pub text: String,
+    /// Color of the text.
+    #[serde(default)]
+    pub color: ColorCode,
+}
+
+/// A terminal color: a name (`"red"`, `"lightblue"`, ...) or an ANSI 256-color code. White by
+/// default.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(try_from = "ColorValue")]
+pub struct ColorCode(pub AnsiValue);
+
+impl ColorCode {
+    // names of the first 16 colors, by code
+    const NAMES: [&'static str; 16] = [
+        "black",
+        "red",
+        "green",
+        "yellow",
+        "blue",
+        "magenta",
+        "cyan",
+        "white",
+        "lightblack",
+        "lightred",
+        "lightgreen",
+        "lightyellow",
+        "lightblue",
+        "lightmagenta",
+        "lightcyan",
+        "lightwhite",
+    ];
+
+    /// Returns the color with the given name, if any.
+    pub fn from_name(name: &str) -> Option<ColorCode> {
+        let code = Self::NAMES.iter().position(|n| *n == name)?;
+        Some(ColorCode(AnsiValue(code as u8)))
+    }
+
+    fn red() -> ColorCode {
+        ColorCode(AnsiValue(1))
+    }
+}
+
+impl Default for ColorCode {
+    fn default() -> Self {
+        ColorCode(AnsiValue(7))
+    }
+}
+
+/// A color as written in the configuration file.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ColorValue {
+    Code(u8),
+    Name(String),
+}
+
+impl TryFrom<ColorValue> for ColorCode {
+    type Error = String;
+
+    fn try_from(value: ColorValue) -> Result<Self, Self::Error> {
+        match value {
+            ColorValue::Code(code) => Ok(ColorCode(AnsiValue(code))),
+            ColorValue::Name(name) => {
+                Self::from_name(&name).ok_or_else(|| format!("unknown color `{}`", name))
+            }
+        }
+    }
+}
+
+impl Config {
+    /// Loads the configuration from the file at `path`, with its sprites.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(|e| ConfigError::Io(path.to_path_buf(), e))?;
+        let dir = path.parent().unwrap_or(Path::new("."));
+        let mut config = Self::parse(&text, path, dir)?;
+        config.source = Some(path.to_path_buf());
+        Ok(config)
+    }
+
+    /// Returns the configuration built into the game, with its sprites (read from the current
+    /// directory).
+    pub fn builtin() -> Result<Self, ConfigError> {
+        Self::parse(BUILTIN, "built-in game.toml", ".")
+    }
+
+    /// Loads the configuration again from where it was loaded (see [`Config::load`] and
+    /// [`Config::builtin`]).
+    pub fn reload(&self) -> Result<Self, ConfigError> {
+        match &self.source {
+            Some(path) => Self::load(path),
+            None => Self::builtin(),
+        }
+    }
+
+    /// Returns the files the configuration was loaded from: the configuration file (unless it is
+    /// the built-in one), the sprites and the scripts.
+    pub fn files(&self) -> Vec<PathBuf> {
+        let scripts = self.enemies.iter().filter_map(|enemy| match enemy {
+            EnemyConfig::Script { script, .. } => Some(script),
+            EnemyConfig::Walls { .. } => None,
+        });
+        self.source
+            .iter()
+            .cloned()
+            .chain(self.sprites.keys().chain(scripts).map(|p| self.dir.join(p)))
+            .collect()
+    }
+
+    /// Parses and validates the configuration `text` of the file at `path`, loading its sprites
+    /// relative to `dir`.
+    pub fn parse<P, D>(text: &str, path: P, dir: D) -> Result<Self, ConfigError>
+    where
+        P: AsRef<Path>,
+        D: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let mut config: Config =
+            toml::from_str(text).map_err(|e| ConfigError::Parse(path.to_path_buf(), e))?;
+        config.dir = dir.as_ref().to_path_buf();
+        config.path = path.to_path_buf();
+        config
+            .validate(dir.as_ref())
+            .map_err(|msg| ConfigError::Invalid(path.to_path_buf(), msg))?;
+        Ok(config)
+    }
+
+    /// Checks that the configuration makes a game and loads its sprites and scripts. On error
+    /// returns what is wrong and where.
+    fn validate(&mut self, dir: &Path) -> Result<(), String> {
+        let layers = &self.stage.layers;
+        if layers.is_empty() {
+            return Err("stage.layers: the stage has no layers".to_string());
+        }
+        if !layers.iter().any(|l| l.name == self.stage.floor) {
+            return Err(format!(
+                "stage.floor: no layer named `{}`",
+                self.stage.floor
+            ));
+        }
+
+        for (at, text) in [
+            ("hud.title", &self.hud.title),
+            ("hud.pause", &self.hud.pause),
+        ] {
+            if text.text.is_empty() {
+                return Err(format!("{}: the text is empty", at));
+            }
+        }
+
+        let mut paths = Vec::new();
+        for (i, layer) in layers.iter().enumerate() {
+            let at = format!("stage.layers[{}] ({})", i, layer.name);
+            if layers[..i].iter().any(|l| l.name == layer.name) {
+                return Err(format!("{}: there is another layer with this name", at));
+            }
+            if layer.name == self.stage.floor && matches!(layer.anchor, Anchor::Floor) {
+                return Err(format!(
+                    "{}: the floor layer can't be anchored to the floor",
+                    at
+                ));
+            }
+
+            match (&layer.sprite, &layer.tiles, layer.terrain, layer.gap) {
+                (Some(sprite), None, None, None | Some(Gap::Fixed(_))) => paths.push(sprite),
+                (Some(_), None, None, Some(Gap::Range(..))) => {
+                    return Err(format!("{}: the gap of a sprite must be a size", at));
+                }
+                (None, Some(tiles), None, gap) => {
+                    if tiles.iter().all(|t| t.weight == 0) {
+                        return Err(format!("{}: at least one tile must have weight", at));
+                    }
+                    if let Some(Gap::Range(min, max)) = gap {
+                        if min > max {
+                            return Err(format!("{}: the gap range is empty", at));
+                        }
+                    }
+                    paths.extend(tiles.iter().map(|t| &t.sprite));
+                }
+                (None, None, Some(height), None) => {
+                    if height == 0 {
+                        return Err(format!("{}: the terrain must have at least one row", at));
+                    }
+                }
+                (None, None, Some(_), Some(_)) => {
+                    return Err(format!("{}: a terrain has no gap", at));
+                }
+                _ => {
+                    return Err(format!(
+                        "{}: expected exactly one of `sprite`, `tiles` or `terrain`",
+                        at
+                    ));
+                }
+            }
+        }
+
+        for (i, enemy) in self.enemies.iter().enumerate() {
+            if enemy.shift() == 0 {
+                return Err(format!("enemies[{}]: the shift must be at least 1", i));
+            }
+            if let EnemyConfig::Script { script, name, .. } = enemy {
+                let at = format!("enemies[{}]: script `{}`", i, script);
+                let path = dir.join(script);
+                let text = fs::read_to_string(&path).map_err(|e| format!("{}: {}", at, e))?;
+                let name = match name {
+                    Some(name) => name.clone(),
+                    None => path
+                        .file_stem()
+                        .map_or_else(|| script.clone(), |s| s.to_string_lossy().into_owned()),
+                };
+                let compiled =
+                    Script::compile(&name, &text).map_err(|e| format!("{}: {}", at, e))?;
+                self.scripts.insert(i, compiled);
+            }
+        }
+
+        for path in paths {
+            if self.sprites.contains_key(path) {
+                continue;
+            }
+            let sprite =
+                Sprite::load(dir.join(path)).map_err(|e| format!("sprite `{}`: {}", path, e))?;
+            self.sprites.insert(path.clone(), sprite);
+        }
+        Ok(())
+    }
+
+    /// Returns the sprite at `path` (as written in the file).
+    fn sprite(&self, path: &str) -> Sprite {
+        self.sprites[path].clone()
+    }
+
+    /// Builds the stage layer described by `layer` for a screen with `cols` columns.
+    fn layer(&self, layer: &LayerConfig, cols: u16) -> Layer {
+        // the random layers are reseeded by the game on each run
+        match (&layer.sprite, &layer.tiles, layer.terrain) {
+            (Some(sprite), ..) => {
+                let mut build = Layer::builder(cols, self.sprite(sprite));
+                if let Some(Gap::Fixed(size)) = layer.gap {
+                    build = build.gap(size);
+                }
+                layer.finish(build)
+            }
+            (_, Some(tiles), _) => {
+                let mut build = Tiles::builder(0);
+                for tile in tiles {
+                    build = build.tile(self.sprite(&tile.sprite), tile.weight);
+                }
+                match layer.gap {
+                    Some(Gap::Fixed(size)) => build = build.gap(size..=size),
+                    Some(Gap::Range(min, max)) => build = build.gap(min..=max),
+                    None => (),
+                }
+                layer.finish(Layer::streamed(cols, build.build()))
+            }
+            (.., terrain) => {
+                let height = terrain.expect("Validated layer without a strip.");
+                layer.finish(Layer::streamed(cols, Terrain::new(height, 0)))
+            }
+        }
+    }
+
+    /// Builds a new game for a screen of `cols` columns and `rows` rows. Fails if the HUD texts
+    /// don't fit in the screen.
+    pub fn build(&self, cols: u16, rows: u16) -> Result<Game, ConfigError> {
+        for (at, text) in [
+            ("hud.title", &self.hud.title),
+            ("hud.pause", &self.hud.pause),
+        ] {
+            if text.text.chars().count() > cols as usize {
+                let msg = format!(
+                    "{}: the text is wider than the screen ({} columns)",
+                    at, cols
+                );
+                return Err(ConfigError::Invalid(self.path.clone(), msg));
+            }
+        }
+
+        let mut stage = Stage::new(cols, rows);
+        for layer in &self.stage.layers {
+            let idx = stage.push_layer(self.layer(layer, cols), layer.color.0);
+            if layer.name == self.stage.floor {
+                stage.set_floor(idx);
+            }
+        }
+
+        let player = Player::new(self.player.icon, self.player.color.0, stage.floor);
+
+        let mut enemies = Enemies::new();
+        for (i, enemy) in self.enemies.iter().enumerate() {
+            let spawn = enemy.spawn();
+            let pos = Pos {
+                col: cols.saturating_sub(spawn.from_right).max(1),
+                row: stage.floor.saturating_sub(spawn.above_floor).max(1),
+            };
+            match enemy {
+                EnemyConfig::Walls {
+                    icon, color, shift, ..
+                } => {
+                    enemies.add_enemy(Walls::new(*icon, pos, *shift).colored(color.0));
+                }
+                EnemyConfig::Script { color, shift, .. } => {
+                    let script = self.scripts[&i].clone();
+                    enemies.add_enemy(Scripted::new(script, pos, *shift).colored(color.0));
+                }
+            }
+        }
+
+        let hud = &self.hud;
+        let splash = Splash::new(
+            &stage.size,
+            &hud.title.text,
+            hud.title.color.0,
+            &hud.pause.text,
+            hud.pause.color.0,
+        );
+        let hud = Hud::new(splash, Controls::new(&stage.size));
+
+        Ok(Game::new(player, stage, enemies, hud))
+    }
+}
+
+/// Errors on loading a [`Config`].
+#[derive(Debug)]
+pub enum ConfigError {
+    /// Failed to read the configuration file.
+    Io(PathBuf, io::Error),
+    /// The configuration file is invalid.
+    Parse(PathBuf, toml::de::Error),
+    /// The configuration doesn't make a game (or some sprite or script can't be loaded, or the
+    /// HUD texts don't fit in the screen).
+    Invalid(PathBuf, String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            Self::Parse(path, e) => write!(f, "{}: {}", path.display(), e),
+            Self::Invalid(path, msg) => write!(f, "{}: {}", path.display(), msg),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
@@ -0,0 +1,26 @@
+//! Standard directories where the game keeps its files.
+
+use std::{env, path::PathBuf};
+
+/// Returns the directory of the game configuration files.
+///
+/// That is `$XDG_CONFIG_HOME/crabbit` or, if `XDG_CONFIG_HOME` is not set, `~/.config/crabbit`.
+pub fn config_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_CONFIG_HOME", ".config")
+}
+
+/// Returns the directory of the game data files.
+///
+/// That is `$XDG_DATA_HOME/crabbit` or, if `XDG_DATA_HOME` is not set, `~/.local/share/crabbit`.
+pub fn data_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_DATA_HOME", ".local/share")
+}
+
+/// Returns `$<var>/crabbit`, falling back to `$HOME/<fallback>/crabbit`.
+fn xdg_dir(var: &str, fallback: &str) -> Option<PathBuf> {
+    let base = match env::var_os(var).filter(|dir| !dir.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env::var_os("HOME")?).join(fallback),
+    };
+    Some(base.join("crabbit"))
+}
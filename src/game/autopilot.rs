@@ -0,0 +1,168 @@
+//! A bot that plays the game.
+
+use crate::{
+    components::{enemies::Enemy, player::PlayerState, Player},
+    game::{headless::Controller, Game},
+    input::{Act, Phase},
+};
+
+/// A [`Controller`] that makes the player jump over the upcoming obstacles of the enemies (see
+/// [`Enemy::obstacles`]).
+///
+/// At each tick, the autopilot works out in which of the next ticks each obstacle overlaps the
+/// column of the player (from the world speed and the width of the obstacle) and plans the jumps
+/// (normal or boosted) that get over all of them, jumping only when the plan can't wait anymore.
+/// If no plan gets over all of them, it follows the one that lasts the longest.
+#[derive(Debug, Default)]
+pub struct Autopilot;
+
+/// When and where an obstacle hits the player.
+#[derive(Debug, Clone)]
+struct Danger {
+    /// First tick in which the obstacle overlaps the column of the player.
+    from: u64,
+    /// Last tick (within the lookahead) in which it does.
+    to: u64,
+    /// Lowest height of the player (above the ground) that the obstacle hits.
+    low: u16,
+    /// Highest height of the player that the obstacle hits.
+    high: u16,
+}
+
+impl Autopilot {
+    // how many ticks ahead the autopilot looks for obstacles (more than the longest jump)
+    const LOOKAHEAD: u64 = 32;
+
+    /// Returns a new `Autopilot`.
+    pub fn new() -> Self {
+        Autopilot
+    }
+
+    /// Returns the height of the player at each tick of a jump of the given `height`, until it
+    /// lands.
+    fn profile(height: u16, boosted: bool) -> Vec<u16> {
+        let top = height + if boosted { Player::MAX_BOOST } else { 0 };
+        (1..=top).chain([top]).chain((0..top).rev()).collect()
+    }
+
+    /// Returns the world steps made by the end of each of the next ticks (see [`Game::tick`]),
+    /// starting with the 0 steps made so far.
+    fn steps(game: &Game) -> Vec<u64> {
+        let mut travel = game.travel;
+        let mut speed = game.speed;
+        let mut score = game.score();
+        let mut total = 0;
+        let ticks = (0..Self::LOOKAHEAD).map(|_| {
+            travel += speed;
+            let steps = travel.floor();
+            travel -= steps;
+            score += steps as u32;
+            speed = game.profile().speed_at(score);
+            total += steps as u64;
+            total
+        });
+        [0].into_iter().chain(ticks).collect()
+    }
+
+    /// Returns the upcoming obstacles that would hit the player, on the ground or jumping.
+    ///
+    /// In each tick the player moves first and then the world makes its steps, the player being
+    /// hit if an obstacle covers its column before or after any of the steps. Thus, an obstacle
+    /// hits the player in the ticks whose steps (including the last one of the previous tick)
+    /// include one after which the obstacle covers the column of the player.
+    fn dangers(game: &Game) -> Vec<Danger> {
+        let pos = game.player().obj.pos;
+        let steps = Self::steps(game);
+
+        game.enemies()
+            .obstacles()
+            .into_iter()
+            .filter(|o| o.row <= pos.row && o.col + o.width > pos.col)
+            .filter_map(|o| {
+                // steps after which the obstacle covers the column of the player
+                let shift = u64::from(o.shift.max(1));
+                let first = u64::from(o.col.saturating_sub(pos.col)).div_ceil(shift);
+                let last = u64::from(o.col + o.width - pos.col).div_ceil(shift) - 1;
+                if last < first {
+                    return None;
+                }
+
+                let from = (1..steps.len()).find(|&t| steps[t] >= first)?;
+                let to = (from..steps.len())
+                    .take_while(|&t| steps[t - 1] <= last)
+                    .last()?;
+                Some(Danger {
+                    from: from as u64,
+                    to: to as u64,
+                    low: (pos.row + 1).saturating_sub(o.row + o.height),
+                    high: pos.row - o.row,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns `true` if any of the `dangers` hits the player at the given `height` in `tick`.
+    fn hit(dangers: &[Danger], tick: u64, height: u16) -> bool {
+        dangers
+            .iter()
+            .any(|d| (d.from..=d.to).contains(&tick) && (d.low..=d.high).contains(&height))
+    }
+
+    /// Returns the phase of the jump action (of the given `height`) to take now, if the player
+    /// must jump.
+    ///
+    /// Works out, from the last tick of the lookahead back to the next one, how long the player
+    /// lasts if it is on the ground at the start of each tick: it either runs through the tick or
+    /// starts a jump, and then does the best from where it is next on the ground. Waiting is
+    /// preferred to jumping, and normal jumps to boosted ones.
+    fn decide(height: u16, dangers: &[Danger]) -> Option<Phase> {
+        let end = Self::LOOKAHEAD + 1;
+        let jumps = [
+            (Some(Phase::Press), Self::profile(height, false)),
+            (Some(Phase::Hold), Self::profile(height, true)),
+        ];
+
+        // the tick up to which the player lasts from each tick (`end` if it gets to the end of the
+        // lookahead) and the phase of the action that gets there.
+        let mut lasts = vec![(end, None); end as usize + 1];
+        for tick in (1..end).rev() {
+            let run = match Self::hit(dangers, tick, 0) {
+                true => tick,
+                false => lasts[tick as usize + 1].0,
+            };
+            let options = jumps.iter().map(|(phase, profile)| {
+                let hit = (tick..end)
+                    .zip(profile)
+                    .find(|&(t, h)| Self::hit(dangers, t, *h));
+                let landing = (tick + profile.len() as u64).min(end);
+                let last = hit.map_or(lasts[landing as usize].0, |(t, _)| t);
+                (last, *phase)
+            });
+            // keeps the first of the options that last the longest
+            lasts[tick as usize] =
+                options.fold(
+                    (run, None),
+                    |best, option| {
+                        if option.0 > best.0 {
+                            option
+                        } else {
+                            best
+                        }
+                    },
+                );
+        }
+        lasts[1].1
+    }
+}
+
+impl Controller for Autopilot {
+    fn act(&mut self, _tick: u64, game: &Game) -> Option<(Act, Phase)> {
+        if !matches!(game.player().state, PlayerState::Running) {
+            return None;
+        }
+
+        let dangers = Self::dangers(game);
+        let height = game.profile().jump_height;
+        Self::decide(height, &dangers).map(|phase| (Act::PlayerJump, phase))
+    }
+}
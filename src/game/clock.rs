@@ -0,0 +1,67 @@
+//! Fixed timestep simulation clock.
+
+use std::time::{Duration, Instant};
+
+/// Splits the real time into fixed-size ticks.
+///
+/// The elapsed time is accumulated and handed out as whole ticks, so the simulation always
+/// advances at the same rate no matter how long rendering (or the terminal) takes.
+pub struct Clock {
+    tick: Duration,
+    last: Instant,
+    acc: Duration,
+}
+
+impl Clock {
+    // maximum amount of ticks handed out at once, so a long stall doesn't freeze the game while it
+    // catches up.
+    const MAX_TICKS: u32 = 5;
+
+    /// Returns a new `Clock` with ticks of length `tick`.
+    pub fn new(tick: Duration) -> Self {
+        Clock {
+            tick,
+            last: Instant::now(),
+            acc: Duration::ZERO,
+        }
+    }
+
+    /// Changes the length of the ticks (e.g. to speed up a replay).
+    pub fn set_tick(&mut self, tick: Duration) {
+        self.tick = tick;
+    }
+
+    /// Returns how many ticks have elapsed since the last call.
+    pub fn ticks(&mut self) -> u32 {
+        let now = Instant::now();
+        self.acc += now - self.last;
+        self.last = now;
+
+        let mut ticks = 0;
+        while self.acc >= self.tick {
+            self.acc -= self.tick;
+            ticks += 1;
+        }
+
+        if ticks > Self::MAX_TICKS {
+            self.acc = Duration::ZERO;
+            ticks = Self::MAX_TICKS;
+        }
+        ticks
+    }
+
+    /// Returns the time left until the next tick.
+    pub fn until_tick(&self) -> Duration {
+        let elapsed = self.acc + self.last.elapsed();
+        self.tick.saturating_sub(elapsed)
+    }
+
+    /// Discards the time elapsed since the last call to [`Clock::ticks`].
+    ///
+    /// Should be called when the simulation was stopped (e.g. the game was paused), otherwise all
+    /// the stopped time would be handed out as ticks.
+    pub fn resume(&mut self) {
+        self.last = Instant::now();
+        self.acc = Duration::ZERO;
+    }
+}
@@ -0,0 +1,164 @@
+//! Difficulty levels and the profiles that define them.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::components::Player;
+
+/// A difficulty level of the game.
+///
+/// Each level, except for the custom one, has a predefined [`Profile`] (see
+/// [`Difficulty::profile`]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    /// A profile defined by the user.
+    Custom,
+}
+
+impl Difficulty {
+    /// All the difficulty levels, from the easiest.
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Normal,
+        Difficulty::Hard,
+        Difficulty::Custom,
+    ];
+
+    /// Returns the name of the level (as written in the settings file).
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+            Difficulty::Custom => "custom",
+        }
+    }
+
+    /// Returns the level with the given name (see [`Difficulty::name`]).
+    pub fn from_name(name: &str) -> Option<Difficulty> {
+        Self::ALL.into_iter().find(|level| level.name() == name)
+    }
+
+    /// Returns the profile of the level, or `None` for the custom level.
+    pub fn profile(&self) -> Option<Profile> {
+        let profile = match self {
+            Difficulty::Easy => Profile {
+                ini_speed: 0.75,
+                max_speed: 1.5,
+                speed_step: 60,
+                speed_inc: 1.0 / 40.0,
+                wall_prob: 12,
+                min_wall: 1,
+                max_wall: 2,
+                jump_height: 3,
+            },
+            Difficulty::Normal => Profile::default(),
+            Difficulty::Hard => Profile {
+                ini_speed: 1.25,
+                max_speed: 2.5,
+                speed_step: 40,
+                speed_inc: 1.0 / 20.0,
+                wall_prob: 22,
+                min_wall: 1,
+                max_wall: 3,
+                jump_height: 4,
+            },
+            Difficulty::Custom => return None,
+        };
+        Some(profile)
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = self.name();
+        write!(f, "{}{}", name[..1].to_uppercase(), &name[1..])
+    }
+}
+
+/// What makes the game easier or harder.
+///
+/// The world speed (world steps per tick) starts at `ini_speed` and increases by `speed_inc`
+/// every `speed_step` points, up to `max_speed`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    /// Initial world speed.
+    pub ini_speed: f64,
+    /// Maximum world speed.
+    pub max_speed: f64,
+    /// How many points are needed for the speed to increase.
+    pub speed_step: u32,
+    /// How much the speed increases at each speed step.
+    pub speed_inc: f64,
+    /// Chance (in percent) of having a wall at each place where a wall can be.
+    pub wall_prob: u32,
+    /// Minimum height of the walls.
+    pub min_wall: u16,
+    /// Maximum height of the walls.
+    pub max_wall: u16,
+    /// Height of the player jump.
+    pub jump_height: u16,
+}
+
+impl Profile {
+    /// Returns the world speed for the given score.
+    pub fn speed_at(&self, score: u32) -> f64 {
+        let steps = score / self.speed_step.max(1);
+        (self.ini_speed + steps as f64 * self.speed_inc).min(self.max_speed)
+    }
+
+    /// Checks if the profile makes a playable game. On error returns what is wrong.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.ini_speed > 0.0 && self.ini_speed.is_finite()) {
+            return Err("the initial speed must be positive".to_string());
+        }
+        if !(self.max_speed >= self.ini_speed && self.max_speed.is_finite()) {
+            return Err("the maximum speed can't be less than the initial speed".to_string());
+        }
+        if !(self.speed_inc >= 0.0 && self.speed_inc.is_finite()) {
+            return Err("the speed increase can't be negative".to_string());
+        }
+        if self.speed_step == 0 {
+            return Err("the speed step must be at least 1".to_string());
+        }
+        if self.wall_prob > 100 {
+            return Err("the wall probability can't be greater than 100".to_string());
+        }
+        if self.min_wall == 0 || self.min_wall > self.max_wall {
+            return Err("the wall heights must be at least 1 and in order".to_string());
+        }
+        if self.jump_height == 0 {
+            return Err("the jump height must be at least 1".to_string());
+        }
+        let highest_jump = self.jump_height.saturating_add(Player::MAX_BOOST);
+        if self.max_wall >= highest_jump {
+            return Err(format!(
+                "the walls must be lower than the highest (boosted) jump, {} rows",
+                highest_jump
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for Profile {
+    /// Returns the profile of the normal difficulty.
+    fn default() -> Self {
+        Profile {
+            ini_speed: 1.0,
+            max_speed: 2.0,
+            speed_step: 50,
+            speed_inc: 1.0 / 30.0,
+            wall_prob: 16,
+            min_wall: 1,
+            max_wall: 2,
+            jump_height: 3,
+        }
+    }
+}
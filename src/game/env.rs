@@ -0,0 +1,266 @@
+//! A reinforcement-learning environment (in the style of Gym) on top of a headless [`Game`].
+
+use crate::{
+    components::{enemies::Enemy, player::PlayerState},
+    game::{
+        headless::{Death, Headless},
+        Game,
+    },
+    graphics::{canvas::Canvas, Render},
+    input::Phase,
+};
+
+/// An action of the agent, taken before each tick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Doesn't press the jump key (releasing it, if it was held).
+    Idle,
+    /// Presses the jump key: a normal jump.
+    Jump,
+    /// Holds the jump key: a higher jump (see [`Player::boost`]), as long as it is held.
+    ///
+    /// [`Player::boost`]: crate::components::Player::boost
+    HighJump,
+}
+
+impl Action {
+    /// All the actions (a discrete action space).
+    pub const ALL: [Action; 3] = [Action::Idle, Action::Jump, Action::HighJump];
+}
+
+/// Which observation the environment returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObservationKind {
+    /// The state of the game (see [`Symbolic`]).
+    Symbolic,
+    /// The characters on the screen.
+    Grid,
+}
+
+/// An upcoming obstacle, as seen from the player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Upcoming {
+    /// How many columns the obstacle is ahead of the player.
+    pub distance: u16,
+    /// How many rows the obstacle reaches above the ground.
+    pub height: u16,
+}
+
+/// The state of the game as seen from the player.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symbolic {
+    /// Row of the player on the screen.
+    pub row: u16,
+    /// How many rows the player is above the ground.
+    pub height: u16,
+    /// Whether the player is jumping (and thus can't jump).
+    pub jumping: bool,
+    /// How many lives the player has left (see [`Game::set_lives`]).
+    pub health: u16,
+    /// The obstacles ahead of the player, nearest first.
+    pub obstacles: Vec<Upcoming>,
+    /// World speed (world steps per tick, each moving the obstacles towards the player).
+    pub speed: f64,
+}
+
+/// What the agent sees.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Observation {
+    /// The state of the game.
+    Symbolic(Symbolic),
+    /// The rows of characters on the screen, as rendered on the terminal.
+    Grid(Vec<Vec<char>>),
+}
+
+/// Extra information about a step.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Info {
+    /// Score of the run.
+    pub score: u32,
+    /// How many ticks the run lasted.
+    pub tick: u64,
+    /// Seed of the run.
+    pub seed: u64,
+    /// How the player died, if it did.
+    pub death: Option<Death>,
+}
+
+/// A reinforcement-learning environment: each step takes an [`Action`] and advances the game by
+/// one tick (see [`Game::tick`]).
+///
+/// The reward of a step is the score made in it, minus `DEATH_PENALTY` if the player dies, which
+/// ends the episode (the run).
+///
+/// # Example
+///
+/// ```
+/// use termion::color;
+/// use crabbit::{
+///     components::{
+///         enemies::{Enemies, Walls},
+///         hud::{Controls, Hud, Splash},
+///         stage::{Layer, Stage},
+///         Player,
+///     },
+///     game::{
+///         env::{Action, Env, Observation, ObservationKind},
+///         Game,
+///     },
+///     graphics::{object::Sprite, Pos},
+/// };
+///
+/// let mut stage = Stage::new(80, 24);
+/// let ground = Layer::builder(80, Sprite::new(vec!['#'], 1)).build();
+/// let floor = stage.push_layer(ground, color::White);
+/// stage.set_floor(floor);
+///
+/// let player = Player::new('O', color::Yellow, stage.floor);
+/// let mut enemies = Enemies::new();
+/// enemies.add_enemy(Walls::new('|', Pos { col: 80, row: stage.floor }, 2));
+/// let splash = Splash::new(&stage.size, "Crabbit", color::Blue, "Paused", color::Blue);
+/// let hud = Hud::new(splash, Controls::new(&stage.size));
+///
+/// let mut env = Env::new(Game::new(player, stage, enemies, hud), ObservationKind::Symbolic);
+/// let mut obs = env.reset(42);
+/// let start = env.info().score;
+/// let mut total = 0.0;
+/// loop {
+///     // jumps when the nearest wall is close
+///     let near = match &obs {
+///         Observation::Symbolic(s) => s.obstacles.first().is_some_and(|o| o.distance <= 4),
+///         Observation::Grid(_) => unreachable!(),
+///     };
+///     let action = if near { Action::Jump } else { Action::Idle };
+///     let (next, reward, done, info) = env.step(action);
+///     total += reward;
+///     obs = next;
+///     if done {
+///         assert_eq!(info.death.unwrap().cause, "wall");
+///         break;
+///     }
+/// }
+/// assert_eq!(total, (env.game().score() - start) as f64 - Env::DEATH_PENALTY);
+///
+/// // the rendered screen can be observed as well
+/// env.set_kind(ObservationKind::Grid);
+/// let pos = env.game().player().obj.pos;
+/// match env.reset(42) {
+///     Observation::Grid(rows) => {
+///         let (row, col) = (pos.row as usize - 1, pos.col as usize - 1);
+///         assert_eq!(rows[row][col], 'O');
+///     }
+///     Observation::Symbolic(_) => unreachable!(),
+/// }
+/// ```
+pub struct Env {
+    headless: Headless,
+    kind: ObservationKind,
+    canvas: Canvas,
+}
+
+impl Env {
+    /// Penalty (negative reward) for the death of the player.
+    pub const DEATH_PENALTY: f64 = 100.0;
+
+    /// Returns a new `Env` of `game` that returns observations of the given `kind`.
+    pub fn new(game: Game, kind: ObservationKind) -> Self {
+        let canvas = Canvas::new(game.size().clone());
+        Env {
+            headless: Headless::new(game),
+            kind,
+            canvas,
+        }
+    }
+
+    /// Returns the game.
+    pub fn game(&self) -> &Game {
+        self.headless.game()
+    }
+
+    /// Changes the kind of the observations.
+    pub fn set_kind(&mut self, kind: ObservationKind) {
+        self.kind = kind;
+    }
+
+    /// Starts a new episode (a run) with the given `seed`. Returns the first observation.
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        self.headless.game_mut().set_seed(Some(seed));
+        self.headless.restart();
+        self.canvas.clear();
+        self.observe()
+    }
+
+    /// Takes the `action` and advances the game by one tick. Returns the observation, the
+    /// reward, whether the episode is over and extra information.
+    ///
+    /// Once the episode is over, the steps don't change the game until the next
+    /// [`reset`](Env::reset).
+    pub fn step(&mut self, action: Action) -> (Observation, f64, bool, Info) {
+        let was_over = self.headless.outcome().death.is_some();
+        let score = self.game().score();
+
+        let phase = match action {
+            Action::Idle => Phase::Release,
+            Action::Jump => Phase::Press,
+            Action::HighJump => Phase::Hold,
+        };
+        self.headless.game_mut().jump(phase);
+        let done = self.headless.step().is_some();
+
+        let mut reward = (self.game().score() - score) as f64;
+        if done && !was_over {
+            reward -= Self::DEATH_PENALTY;
+        }
+        (self.observe(), reward, done, self.info())
+    }
+
+    /// Returns the extra information about the current state.
+    pub fn info(&self) -> Info {
+        let outcome = self.headless.outcome();
+        Info {
+            score: outcome.score,
+            tick: outcome.ticks,
+            seed: self.game().seed(),
+            death: outcome.death,
+        }
+    }
+
+    /// Returns the observation of the current state.
+    pub fn observe(&mut self) -> Observation {
+        match self.kind {
+            ObservationKind::Symbolic => Observation::Symbolic(self.symbolic()),
+            ObservationKind::Grid => {
+                self.headless.game().render(&mut self.canvas);
+                Observation::Grid(self.canvas.rows().map(<[char]>::to_vec).collect())
+            }
+        }
+    }
+
+    /// Returns the symbolic observation of the current state.
+    fn symbolic(&self) -> Symbolic {
+        let game = self.game();
+        let player = game.player();
+        let pos = player.obj.pos;
+        let ground = player.ground();
+
+        let obstacles = game
+            .enemies()
+            .obstacles()
+            .into_iter()
+            .filter(|o| o.col >= pos.col && o.row <= ground)
+            .map(|o| Upcoming {
+                distance: o.col - pos.col,
+                height: ground + 1 - o.row,
+            })
+            .collect();
+
+        Symbolic {
+            row: pos.row,
+            height: player.height(),
+            jumping: matches!(player.state, PlayerState::Jumping),
+            health: game.health(),
+            obstacles,
+            speed: game.speed(),
+        }
+    }
+}
@@ -0,0 +1,122 @@
+//! Events of the game, published by the components and consumed by the subscribers (e.g. the HUD)
+//! at the end of each tick.
+
+use crate::components::enemies::Obstacle;
+
+/// Something that happened in the game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameEvent {
+    /// The player started a jump of the given height.
+    PlayerJumped { height: u16 },
+    /// The player got back to the ground.
+    PlayerLanded,
+    /// An enemy (by its [name]) hit the player, taking `damage` lives and leaving it with
+    /// `health` lives.
+    ///
+    /// [name]: crate::components::enemies::Enemy::name
+    PlayerHit {
+        enemy: String,
+        damage: u16,
+        health: u16,
+    },
+    /// An obstacle appeared on the screen.
+    ObstacleSpawned(Obstacle),
+    /// An obstacle got behind the player.
+    ObstaclePassed(Obstacle),
+    /// The score reached a multiple of [`EventBus::MILESTONE`].
+    ScoreMilestone(u32),
+    /// The score got over the best score of the previous runs (`record`), once per run.
+    RecordBroken { record: u32 },
+    /// The campaign reached the given level (see [`Mode::LEVELS`]), counting from 1.
+    ///
+    /// [`Mode::LEVELS`]: crate::game::Mode::LEVELS
+    LevelReached(u32),
+    /// Extra points, added to the score by the HUD.
+    Bonus(u32),
+    /// An event of a scripted enemy (see [`Scripted`]), by the name of the enemy and of the
+    /// event.
+    ///
+    /// [`Scripted`]: crate::components::enemies::Scripted
+    Scripted { enemy: String, name: String },
+}
+
+/// Consumes the events of the game.
+pub trait Subscriber {
+    /// Reacts to an `event`.
+    fn notify(&mut self, event: &GameEvent);
+}
+
+/// Collects the events published during a tick and delivers them to the given subscribers at the
+/// end of the tick.
+///
+/// # Example
+///
+/// ```
+/// use crabbit::game::events::{EventBus, GameEvent, Subscriber};
+///
+/// struct Jumps(u32);
+///
+/// impl Subscriber for Jumps {
+///     fn notify(&mut self, event: &GameEvent) {
+///         if let GameEvent::PlayerJumped { .. } = event {
+///             self.0 += 1;
+///         }
+///     }
+/// }
+///
+/// let mut jumps = Jumps(0);
+/// let mut bus = EventBus::new();
+///
+/// bus.publish(GameEvent::PlayerJumped { height: 3 });
+/// bus.publish(GameEvent::PlayerLanded);
+/// assert!(bus.events().is_empty());
+/// bus.dispatch(&mut [&mut jumps]);
+/// assert_eq!(jumps.0, 1);
+/// assert_eq!(bus.events().len(), 2);
+///
+/// // the delivered events are kept until the next dispatch
+/// bus.dispatch(&mut []);
+/// assert!(bus.events().is_empty());
+/// ```
+#[derive(Default)]
+pub struct EventBus {
+    pending: Vec<GameEvent>,
+    delivered: Vec<GameEvent>,
+}
+
+impl EventBus {
+    /// Points between two [`GameEvent::ScoreMilestone`].
+    pub const MILESTONE: u32 = 100;
+
+    /// Returns a new `EventBus` without events.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Publishes an `event`, delivered on the next [`dispatch`](EventBus::dispatch).
+    pub fn publish(&mut self, event: GameEvent) {
+        self.pending.push(event);
+    }
+
+    /// Returns the events delivered on the last dispatch, in order.
+    pub fn events(&self) -> &[GameEvent] {
+        &self.delivered
+    }
+
+    /// Delivers the published events to the `subscribers`, in order (all the events to the first
+    /// subscriber, then to the next one...).
+    pub fn dispatch(&mut self, subscribers: &mut [&mut dyn Subscriber]) {
+        self.delivered = std::mem::take(&mut self.pending);
+        for subscriber in subscribers {
+            self.delivered
+                .iter()
+                .for_each(|event| subscriber.notify(event));
+        }
+    }
+
+    /// Discards the published and the delivered events.
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.delivered.clear();
+    }
+}
@@ -0,0 +1,211 @@
+//! Runs a [`Game`] without a terminal.
+
+use crate::{
+    components::DynComp,
+    game::Game,
+    input::{Act, Phase},
+};
+
+/// Decides the action taken (if any) before each tick of a headless run.
+pub trait Controller {
+    /// Returns the action to take before the given `tick` of the current run.
+    fn act(&mut self, tick: u64, game: &Game) -> Option<(Act, Phase)>;
+}
+
+impl<F> Controller for F
+where
+    F: FnMut(u64, &Game) -> Option<(Act, Phase)>,
+{
+    fn act(&mut self, tick: u64, game: &Game) -> Option<(Act, Phase)> {
+        self(tick, game)
+    }
+}
+
+/// A [`Controller`] that takes predefined actions at predefined ticks.
+#[derive(Debug, Clone)]
+pub struct Script {
+    acts: Vec<(u64, Act, Phase)>,
+    next: usize,
+}
+
+impl Script {
+    /// Returns a new `Script` that takes each action at its tick. The actions must be in the order
+    /// of their ticks (at most one action per tick).
+    pub fn new(acts: Vec<(u64, Act, Phase)>) -> Self {
+        Script { acts, next: 0 }
+    }
+}
+
+impl Controller for Script {
+    fn act(&mut self, tick: u64, _game: &Game) -> Option<(Act, Phase)> {
+        match self.acts.get(self.next) {
+            Some(&(t, act, phase)) if t <= tick => {
+                self.next += 1;
+                Some((act, phase))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// How the player died.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Death {
+    /// Name of the enemy that hit the player (see [`Enemy::name`]).
+    ///
+    /// [`Enemy::name`]: crate::components::enemies::Enemy::name
+    pub cause: String,
+    /// Tick (of the run) in which the player died.
+    pub tick: u64,
+}
+
+/// Result of a headless run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// Score at the end of the run.
+    pub score: u32,
+    /// How many ticks the run lasted.
+    pub ticks: u64,
+    /// How the player died, if it did.
+    pub death: Option<Death>,
+}
+
+/// Runs a [`Game`] without a terminal, as fast as possible.
+///
+/// The game advances in the same ticks of an interactive game (see [`Game::tick`]), but the
+/// actions come from a [`Controller`] instead of the user and nothing is rendered.
+///
+/// # Example
+///
+/// ```
+/// use termion::color;
+/// use crabbit::{
+///     components::{
+///         enemies::{Enemies, Walls},
+///         hud::{Controls, Hud, Splash},
+///         stage::{Layer, Stage},
+///         Player,
+///     },
+///     game::{headless::Headless, Game},
+///     graphics::{object::Sprite, Pos},
+///     input::{Act, Phase},
+/// };
+///
+/// let mut stage = Stage::new(80, 24);
+/// let ground = Layer::builder(80, Sprite::new(vec!['#'], 1)).build();
+/// let floor = stage.push_layer(ground, color::White);
+/// stage.set_floor(floor);
+///
+/// let player = Player::new('O', color::Yellow, stage.floor);
+/// let mut enemies = Enemies::new();
+/// enemies.add_enemy(Walls::new('|', Pos { col: 80, row: stage.floor }, 2));
+/// let splash = Splash::new(&stage.size, "Crabbit", color::Blue, "Paused", color::Blue);
+/// let hud = Hud::new(splash, Controls::new(&stage.size));
+///
+/// let mut game = Game::new(player, stage, enemies, hud);
+/// game.set_seed(Some(42));
+///
+/// // never jumps, so it soon hits a wall
+/// let mut headless = Headless::new(game);
+/// let outcome = headless.run(1000, &mut |_, _: &Game| None);
+/// assert_eq!(outcome.death.unwrap().cause, "wall");
+///
+/// // the same seed and the same actions always have the same outcome
+/// headless.restart();
+/// assert_eq!(headless.run(1000, &mut |_, _: &Game| None).score, outcome.score);
+/// ```
+pub struct Headless {
+    game: Game,
+    tick: u64,
+    death: Option<Death>,
+    quit: bool,
+}
+
+impl Headless {
+    /// Returns a new `Headless` that starts a new run of `game`.
+    pub fn new(game: Game) -> Self {
+        let mut headless = Headless {
+            game,
+            tick: 0,
+            death: None,
+            quit: false,
+        };
+        headless.restart();
+        headless
+    }
+
+    /// Returns the game.
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// Returns a mutable reference to the game.
+    pub fn game_mut(&mut self) -> &mut Game {
+        &mut self.game
+    }
+
+    /// Returns how many ticks the current run lasted.
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Starts a new run.
+    pub fn restart(&mut self) {
+        self.game.reset();
+        self.game.update();
+        self.tick = 0;
+        self.death = None;
+        self.quit = false;
+    }
+
+    /// Takes an action: the jump, the restart or the quit (which ends the run).
+    pub fn apply(&mut self, act: Act, phase: Phase) {
+        match act {
+            Act::PlayerJump => self.game.jump(phase),
+            Act::Restart => self.restart(),
+            Act::Quit => self.quit = true,
+            _ => (),
+        }
+    }
+
+    /// Advances the current run by one tick. Returns how the player died, if it did.
+    ///
+    /// Once the player dies, the run doesn't advance anymore until it is restarted.
+    pub fn step(&mut self) -> Option<&Death> {
+        if self.death.is_none() {
+            let hit = self.game.tick();
+            self.tick += 1;
+            if hit {
+                let cause = self.game.death_cause().unwrap_or("unknown").to_string();
+                self.death = Some(Death {
+                    cause,
+                    tick: self.tick,
+                });
+            }
+        }
+        self.death.as_ref()
+    }
+
+    /// Runs the current run for (at most) `ticks` ticks, until the player dies or until the
+    /// `controller` quits, taking the actions decided by the `controller`.
+    pub fn run<C: Controller>(&mut self, ticks: u64, controller: &mut C) -> Outcome {
+        for _ in 0..ticks {
+            if let Some((act, phase)) = controller.act(self.tick, &self.game) {
+                self.apply(act, phase);
+            }
+            if self.quit || self.step().is_some() {
+                break;
+            }
+        }
+        self.outcome()
+    }
+
+    /// Returns the outcome of the current run (so far).
+    pub fn outcome(&self) -> Outcome {
+        Outcome {
+            score: self.game.score(),
+            ticks: self.tick,
+            death: self.death.clone(),
+        }
+    }
+}
@@ -2,52 +2,495 @@
 //!
 //! [`components`]: crate::components
 
+use rand::SeedableRng;
+
 use crate::{
-    components::{enemies::Enemies, hud::Hud, DynComp, Player, Stage},
-    graphics::{Render, TermOut},
+    components::{
+        enemies::{Enemies, Enemy},
+        hud::{Hud, Summary},
+        player::PlayerState,
+        DynComp, GameRng, Player, Stage,
+    },
+    graphics::{Render, Size, TermOut},
+    input::Phase,
+    settings::Settings,
 };
 
-mod runner;
-use runner::Runner;
+pub mod autopilot;
+mod clock;
+pub mod difficulty;
+pub mod env;
+pub mod events;
+pub mod headless;
+pub mod mode;
+pub mod reload;
+pub mod replay;
+pub mod runner;
+pub use difficulty::{Difficulty, Profile};
+pub use events::{EventBus, GameEvent, Subscriber};
+pub use mode::Mode;
+pub use runner::Runner;
+
+// how many ticks the player is invulnerable after losing a life
+const INVULNERABLE_TICKS: u16 = 24;
 
 /// A jumper game. Aggregates all the `components` in a sigle structure.
+///
+/// All the randomness of a run comes from a single generator created from the run seed, so the
+/// same seed and the same user actions always produce the same run.
+///
+/// By default any hit kills the player, but the player can have more lives (see
+/// [`Game::set_lives`]). How fast the game goes, the walls and the player jump depend on the
+/// difficulty (see [`Game::set_difficulty`]), and the rules of the runs on the mode (see
+/// [`Game::set_mode`]).
+///
+/// What happens in each tick is published as events (see [`GameEvent`]), delivered at the end of
+/// the tick to the HUD and to the other subscribers of the tick (see [`Game::tick_with`]).
 pub struct Game {
     player: Player,
     stage: Stage,
     enemies: Enemies,
     hud: Hud,
+    speed: f64,
+    travel: f64,
+    distance: u32,
+    jumps: u32,
+    lives: u16,
+    health: u16,
+    difficulty: Difficulty,
+    chosen: Profile,
+    profile: Profile,
+    mode: Mode,
+    level: usize,
+    ticks: u32,
+    time_up: bool,
+    events: EventBus,
+    milestone: u32,
+    broke_record: bool,
+    fixed_seed: Option<u64>,
+    seed: u64,
+    rng: GameRng,
 }
 
 impl Game {
-    /// Returns a new instance of `Game`.
+    /// Returns a new instance of `Game`. Each run has a random seed.
     pub fn new(player: Player, stage: Stage, enemies: Enemies, hud: Hud) -> Self {
-        Game {
+        let mut game = Game {
             player,
             enemies,
             stage,
             hud,
+            speed: Profile::default().ini_speed,
+            travel: 0.0,
+            distance: 0,
+            jumps: 0,
+            lives: 1,
+            health: 1,
+            difficulty: Difficulty::Normal,
+            chosen: Profile::default(),
+            profile: Profile::default(),
+            mode: Mode::default(),
+            level: 0,
+            ticks: 0,
+            time_up: false,
+            events: EventBus::new(),
+            milestone: 0,
+            broke_record: false,
+            fixed_seed: None,
+            seed: 0,
+            rng: GameRng::seed_from_u64(0),
+        };
+        game.enemies.set_profile(&game.profile);
+        game.reseed();
+        game
+    }
+
+    /// Makes every run use `seed` (or a random seed, if `None`) from now on.
+    pub fn set_seed(&mut self, seed: Option<u64>) {
+        self.fixed_seed = seed;
+        self.reseed();
+    }
+
+    /// Changes how many lives (at least one) the player has from the next run on.
+    ///
+    /// Each hit takes as many lives as its damage (see [`Enemy::hits`]) and makes the player
+    /// invulnerable for a short time. The player dies when it has no lives left.
+    pub fn set_lives(&mut self, lives: u16) {
+        self.lives = lives.max(1);
+        self.hud.set_health(self.health, self.lives);
+    }
+
+    /// Changes the difficulty of the game (and its `profile`, see [`Difficulty::profile`]) from
+    /// the next run on. The campaign has its own difficulty (see [`Mode::LEVELS`]).
+    pub fn set_difficulty(&mut self, difficulty: Difficulty, profile: Profile) {
+        self.difficulty = difficulty;
+        self.chosen = profile;
+        if self.mode != Mode::Campaign {
+            self.profile = profile;
+            self.enemies.set_profile(&profile);
+        }
+    }
+
+    /// Returns the difficulty of the game.
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    /// Returns the profile of the difficulty of the game (see [`Game::set_difficulty`]).
+    pub fn difficulty_profile(&self) -> &Profile {
+        &self.chosen
+    }
+
+    /// Returns the profile of the current run: the one of the difficulty of the game or, in the
+    /// campaign, the one of the current level.
+    pub fn profile(&self) -> &Profile {
+        &self.profile
+    }
+
+    /// Changes the mode of the game from the next run on.
+    pub fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+
+    /// Returns the mode of the game.
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Returns the difficulty of the current run (see [`Game::profile`]).
+    fn run_difficulty(&self) -> Difficulty {
+        match self.mode {
+            Mode::Campaign => Mode::LEVELS[self.level].0,
+            _ => self.difficulty,
+        }
+    }
+
+    /// Changes the profile to the one of the current run (see [`Game::profile`]).
+    fn apply_profile(&mut self) {
+        self.profile = match self.mode {
+            Mode::Campaign => {
+                let difficulty = self.run_difficulty();
+                difficulty
+                    .profile()
+                    .expect("Campaign level without profile.")
+            }
+            _ => self.chosen,
+        };
+        self.enemies.set_profile(&self.profile);
+    }
+
+    /// Returns how many lives the player has at the start of a run.
+    pub fn lives(&self) -> u16 {
+        self.lives
+    }
+
+    /// Returns how many lives the player has left.
+    pub fn health(&self) -> u16 {
+        self.health
+    }
+
+    /// Returns `true` if every run uses the same seed (see [`Game::set_seed`]).
+    pub fn is_seeded(&self) -> bool {
+        self.fixed_seed.is_some()
+    }
+
+    /// Returns the seed of the current run.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Starts the random generation of a new run.
+    fn reseed(&mut self) {
+        self.seed = self.fixed_seed.unwrap_or_else(rand::random);
+        self.rng = GameRng::seed_from_u64(self.seed);
+        self.stage.reseed(&mut self.rng);
+        self.enemies.reseed(&mut self.rng);
+        self.hud.set_seed(self.seed);
+    }
+
+    /// Displays the game as `look` (a game built for a screen of the same size), keeping the run
+    /// going: the stage is replaced and the player and the enemies take their icons and colors.
+    ///
+    /// The stage of `look` must have the floor on the same row and the same enemies (see
+    /// [`Enemies::restyle`]), since the run takes place on them. Otherwise nothing changes and
+    /// returns what is different.
+    pub fn restyle(&mut self, mut look: Game) -> Result<(), String> {
+        if look.stage.floor != self.stage.floor {
+            return Err(format!(
+                "the floor moved from row {} to row {}",
+                self.stage.floor, look.stage.floor
+            ));
+        }
+        self.enemies.restyle(&look.enemies)?;
+
+        // the stage doesn't change the run, so its randomness doesn't come from the run rng
+        look.stage.reseed(&mut GameRng::seed_from_u64(self.seed));
+        self.stage = look.stage;
+        self.player.restyle(&look.player);
+        Ok(())
+    }
+
+    /// Returns the player.
+    pub fn player(&self) -> &Player {
+        &self.player
+    }
+
+    /// Returns the size of the screen (the stage).
+    pub fn size(&self) -> &Size {
+        &self.stage.size
+    }
+
+    /// Returns the enemies.
+    pub fn enemies(&self) -> &Enemies {
+        &self.enemies
+    }
+
+    /// Returns the current score.
+    pub fn score(&self) -> u32 {
+        self.hud.score().current()
+    }
+
+    /// Returns the world speed (world steps per tick).
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    /// Returns the results of the current run.
+    pub fn summary(&self) -> Summary {
+        let score = self.hud.score();
+        Summary {
+            score: score.current(),
+            record: score.record(),
+            distance: self.distance,
+            jumps: self.jumps,
+            difficulty: self.run_difficulty().to_string(),
+            mode: self.mode.to_string(),
+            ranked: self.mode.is_ranked(),
         }
     }
 
-    /// Runs the game on the terminal `out`.
-    pub fn run(self, out: &mut TermOut) {
-        Runner::new(self).run(out);
+    /// Returns the events of the last tick.
+    pub fn events(&self) -> &[GameEvent] {
+        self.events.events()
+    }
+
+    /// Returns the name of the enemy that hits the player, if any.
+    pub fn death_cause(&self) -> Option<&str> {
+        if self.time_up {
+            return Some("time");
+        }
+        self.enemies
+            .hitter(&self.player.obj.pos)
+            .map(|enemy| enemy.name())
+    }
+
+    /// Makes the player jump according to the `phase` of the jump action: while the action is
+    /// held the player jumps higher (see [`Player::boost`]) and keeps jumping.
+    pub fn jump(&mut self, phase: Phase) {
+        match phase {
+            Phase::Press => {
+                self.start_jump();
+                self.player.boost(false);
+            }
+            Phase::Hold => {
+                self.start_jump();
+                self.player.boost(true);
+            }
+            Phase::Release => self.player.boost(false),
+        }
+    }
+
+    /// Makes the player jump, if it is running, counting the jump.
+    fn start_jump(&mut self) {
+        if let PlayerState::Running = self.player.state {
+            let height = self.profile.jump_height;
+            self.player.jump(height);
+            self.jumps += 1;
+            self.events.publish(GameEvent::PlayerJumped { height });
+        }
+    }
+
+    /// Advances the game by one simulation tick. Returns `true` if the player was killed (see
+    /// [`Player::kill`]).
+    ///
+    /// The player moves once per tick while the world (stage, enemies and score) moves according
+    /// to its speed, which grows with the score. Thus, a faster game means more world steps per
+    /// tick and not more ticks per second.
+    ///
+    /// The events of the tick (and those published since the last tick, e.g. a jump) are
+    /// delivered to the HUD at its end.
+    pub fn tick(&mut self) -> bool {
+        self.tick_with(&mut [])
+    }
+
+    /// Advances the game by one simulation tick, like [`Game::tick`], delivering the events of the
+    /// tick to the `subscribers` too (after the HUD).
+    pub fn tick_with(&mut self, subscribers: &mut [&mut dyn Subscriber]) -> bool {
+        let killed = self.advance();
+        let mut all: Vec<&mut dyn Subscriber> = vec![&mut self.hud];
+        for subscriber in subscribers.iter_mut() {
+            all.push(&mut **subscriber);
+        }
+        self.events.dispatch(&mut all);
+        killed
+    }
+
+    /// Moves the player and the world of a tick (see [`Game::tick`]). Returns `true` if the player
+    /// was killed.
+    fn advance(&mut self) -> bool {
+        self.ticks += 1;
+        if self.mode == Mode::TimeAttack {
+            let left = Mode::TIME_LIMIT.saturating_sub(self.ticks);
+            self.hud.set_timer(Some(Mode::seconds(left)));
+            if left == 0 {
+                self.time_up = true;
+                self.player.kill();
+                return true;
+            }
+        }
+
+        let jumping = matches!(self.player.state, PlayerState::Jumping);
+        self.player.update();
+        if jumping && matches!(self.player.state, PlayerState::Running) {
+            self.events.publish(GameEvent::PlayerLanded);
+        }
+        if self.hurt_player() {
+            return true;
+        }
+
+        self.travel += self.speed;
+        while self.travel >= 1.0 {
+            self.travel -= 1.0;
+            self.step_world();
+            if self.hurt_player() {
+                return true;
+            }
+        }
+
+        self.speed = self.profile.speed_at(self.hud.score().current());
+        false
+    }
+
+    /// Moves the world (stage, enemies and score) by one step.
+    fn step_world(&mut self) {
+        self.distance += 1;
+        self.stage.update();
+        self.enemies.watch(&self.player.obj.pos);
+        self.enemies.update();
+        self.enemies.publish(&mut self.events);
+        self.hud.update();
+
+        let col = self.player.obj.pos.col;
+        for obstacle in self.enemies.obstacles() {
+            // the obstacles move `shift` columns per step, so they pass the player only once
+            let end = obstacle.col + obstacle.width;
+            if end <= col && end + obstacle.shift > col {
+                self.events.publish(GameEvent::ObstaclePassed(obstacle));
+            }
+        }
+
+        // the bonuses can make the score skip a milestone or the record
+        let score = self.hud.score();
+        let (current, record) = (score.current(), score.record());
+        let milestone = current - current % EventBus::MILESTONE;
+        if milestone > self.milestone {
+            self.milestone = milestone;
+            self.events.publish(GameEvent::ScoreMilestone(milestone));
+        }
+        if record > 0 && current > record && !self.broke_record && self.mode.is_ranked() {
+            self.broke_record = true;
+            self.events.publish(GameEvent::RecordBroken { record });
+        }
+
+        let (_, end) = Mode::LEVELS[self.level];
+        if self.mode == Mode::Campaign && current >= end {
+            self.level += 1;
+            self.apply_profile();
+            self.events
+                .publish(GameEvent::LevelReached(self.level as u32 + 1));
+        }
+    }
+
+    /// Takes the damage of the enemies that hit the player, unless it is invulnerable. Returns
+    /// `true` if the player was killed.
+    fn hurt_player(&mut self) -> bool {
+        if self.player.is_invulnerable() {
+            return false;
+        }
+        let damage = self.enemies.hits(&self.player.obj.pos);
+        if damage == 0 {
+            return false;
+        }
+
+        // a practice hit only makes the player blink
+        if self.mode == Mode::Practice {
+            let enemy = self.death_cause().unwrap_or_default().to_string();
+            self.events.publish(GameEvent::PlayerHit {
+                enemy,
+                damage: 0,
+                health: self.health,
+            });
+            self.player.hurt(INVULNERABLE_TICKS);
+            return false;
+        }
+
+        self.health = self.health.saturating_sub(damage);
+        self.hud.set_health(self.health, self.lives);
+        let enemy = self.death_cause().unwrap_or_default().to_string();
+        self.events.publish(GameEvent::PlayerHit {
+            enemy,
+            damage,
+            health: self.health,
+        });
+        if self.health == 0 {
+            self.player.kill();
+            true
+        } else {
+            self.player.hurt(INVULNERABLE_TICKS);
+            false
+        }
+    }
+
+    /// Runs the game on the terminal `out` with the user `settings`.
+    ///
+    /// See [`Runner`] to record the session, play back a recorded one or let the autopilot play.
+    pub fn run(self, settings: Settings, out: &mut TermOut) {
+        Runner::new(self, settings).run(out);
     }
 }
 
 impl DynComp for Game {
     fn update(&mut self) {
-        self.stage.update();
-        self.enemies.update();
+        self.step_world();
         self.player.update();
-        self.hud.update();
     }
 
+    /// Resets all the components and starts a new run.
     fn reset(&mut self) {
+        // the score of a practice run doesn't count for the record
+        if !self.mode.is_ranked() {
+            self.hud.score_mut().discard();
+        }
+        self.level = 0;
+        self.apply_profile();
+        self.ticks = 0;
+        self.time_up = false;
+        let timer = (self.mode == Mode::TimeAttack).then(|| Mode::seconds(Mode::TIME_LIMIT));
+        self.hud.set_timer(timer);
+        self.speed = self.profile.ini_speed;
+        self.travel = 0.0;
+        self.distance = 0;
+        self.jumps = 0;
+        self.health = self.lives;
+        self.hud.set_health(self.health, self.lives);
         self.stage.reset();
         self.enemies.reset();
         self.player.reset();
         self.hud.reset();
+        self.events.clear();
+        self.milestone = 0;
+        self.broke_record = false;
+        self.reseed();
     }
 }
 
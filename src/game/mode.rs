@@ -0,0 +1,78 @@
+//! Game modes: the rules of a run other than its difficulty.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::{runner::TICK, Difficulty};
+
+/// A game mode.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Mode {
+    /// The run goes on until the player dies.
+    #[default]
+    Endless,
+    /// The run goes through levels of growing difficulty (see [`Mode::LEVELS`]), ignoring the
+    /// chosen one.
+    Campaign,
+    /// The run ends when the time runs out (see [`Mode::TIME_LIMIT`]), if the player doesn't die
+    /// before.
+    TimeAttack,
+    /// The hits don't take lives and the runs don't count for the record or the statistics.
+    Practice,
+}
+
+impl Mode {
+    /// All the modes, in the order that they are displayed.
+    pub const ALL: [Mode; 4] = [
+        Mode::Endless,
+        Mode::Campaign,
+        Mode::TimeAttack,
+        Mode::Practice,
+    ];
+    /// Levels of the campaign: the difficulty of each level and the score that finishes it. The
+    /// last level has no end.
+    pub const LEVELS: [(Difficulty, u32); 3] = [
+        (Difficulty::Easy, 300),
+        (Difficulty::Normal, 800),
+        (Difficulty::Hard, u32::MAX),
+    ];
+    /// How many ticks a time attack lasts (a minute).
+    pub const TIME_LIMIT: u32 = (60_000 / TICK) as u32;
+
+    /// Returns the name of the mode (as written in the command line).
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mode::Endless => "endless",
+            Mode::Campaign => "campaign",
+            Mode::TimeAttack => "time-attack",
+            Mode::Practice => "practice",
+        }
+    }
+
+    /// Returns the mode with the given name (see [`Mode::name`]).
+    pub fn from_name(name: &str) -> Option<Mode> {
+        Self::ALL.into_iter().find(|mode| mode.name() == name)
+    }
+
+    /// Returns `true` if the runs of the mode count for the record and the statistics.
+    pub fn is_ranked(&self) -> bool {
+        *self != Mode::Practice
+    }
+
+    /// Returns the seconds of `ticks` (rounded up), to display the time of a time attack.
+    pub fn seconds(ticks: u32) -> u32 {
+        (ticks as u64 * TICK).div_ceil(1000) as u32
+    }
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mode::Endless => write!(f, "Endless"),
+            Mode::Campaign => write!(f, "Campaign"),
+            Mode::TimeAttack => write!(f, "Time Attack"),
+            Mode::Practice => write!(f, "Practice"),
+        }
+    }
+}
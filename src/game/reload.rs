@@ -0,0 +1,101 @@
+//! Hot reload of the game config and of the files it uses, for the development of themes (see
+//! [`Runner::hot_reload`]).
+//!
+//! The files are polled for changes (by their modification time) every [`Reload::POLL`]. When
+//! one changes, the config is loaded again and the running game is restyled with it (see
+//! [`Game::restyle`]): the stage layers and their sprites and colors, the player and the enemy
+//! icons and colors are replaced without restarting the run. Anything else (e.g. the enemy
+//! scripts) is only checked, and applies when the game is launched again.
+//!
+//! A config that fails to load or that can't restyle the game is displayed as an alert over the
+//! game (see [`Hud::set_alert`]) until it is fixed.
+//!
+//! [`Runner::hot_reload`]: crate::game::Runner::hot_reload
+//! [`Hud::set_alert`]: crate::components::hud::Hud::set_alert
+
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::{config::Config, game::Game};
+
+/// Reloads a [`Config`] into a running [`Game`] when its files change (see the
+/// [module docs](self)).
+pub struct Reload {
+    config: Config,
+    stamps: Vec<(PathBuf, Option<SystemTime>)>,
+    next_poll: Instant,
+}
+
+impl Reload {
+    /// Time between two checks of the files.
+    pub const POLL: Duration = Duration::from_millis(500);
+
+    /// Returns a new `Reload` of the files of `config`, the config of the running game.
+    pub fn new(config: Config) -> Self {
+        let stamps = Self::stamps(config.files());
+        Reload {
+            config,
+            stamps,
+            next_poll: Instant::now() + Self::POLL,
+        }
+    }
+
+    /// Returns the modification time of each file (`None` if it can't be read).
+    fn stamps(files: Vec<PathBuf>) -> Vec<(PathBuf, Option<SystemTime>)> {
+        files
+            .into_iter()
+            .map(|path| {
+                let stamp = fs::metadata(&path).and_then(|m| m.modified()).ok();
+                (path, stamp)
+            })
+            .collect()
+    }
+
+    /// Checks the files, if it is time to, and reloads the config into `game` if some of them has
+    /// changed. Returns `true` if the game was restyled or its alert changed (so the screen must
+    /// be redrawn).
+    pub fn poll(&mut self, game: &mut Game) -> bool {
+        let now = Instant::now();
+        if now < self.next_poll {
+            return false;
+        }
+        self.next_poll = now + Self::POLL;
+
+        let paths = self.stamps.iter().map(|(path, _)| path.clone()).collect();
+        let stamps = Self::stamps(paths);
+        if stamps == self.stamps {
+            return false;
+        }
+        self.stamps = stamps;
+
+        let reloaded = self.config.reload().map_err(|e| e.to_string());
+        let restyled = reloaded.and_then(|config| {
+            let size = game.size();
+            let look = config
+                .build(size.width, size.height)
+                .map_err(|e| e.to_string())?;
+            game.restyle(look)
+                .map_err(|e| format!("{}; relaunch the game to apply it", e))?;
+            Ok(config)
+        });
+        match restyled {
+            Ok(config) => {
+                self.stamps = Self::stamps(config.files());
+                self.config = config;
+                game.hud.set_alert(Vec::new());
+            }
+            Err(e) => {
+                let width = game.size().width as usize;
+                let lines = format!("Reload failed: {}", e)
+                    .lines()
+                    .map(|line| line.chars().take(width).collect())
+                    .collect();
+                game.hud.set_alert(lines);
+            }
+        }
+        true
+    }
+}
@@ -0,0 +1,351 @@
+//! Recording and playback of runs.
+//!
+//! A replay stores the size of the screen, the lives of the player, the difficulty, the game mode,
+//! the seed of each run and the tick of each user action that changes the game. Since the game is
+//! deterministic, that is enough to reproduce the whole session. The outcome of each run (the score
+//! at the tick of each death) is stored as well, so a playback that doesn't reproduce it is
+//! detected.
+//!
+//! Replays are saved as text, one entry per line:
+//!
+//! ```text
+//! crabbit-replay 1
+//! size 100 30
+//! lives 3
+//! difficulty normal 1 2 50 0.03333333333333333 16 1 2 3
+//! mode endless
+//! 0 run 8123
+//! 42 jump press
+//! 45 jump release
+//! 310 over 268
+//! 310 run 77
+//! 502 end 180
+//! ```
+
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    game::{Difficulty, Mode, Profile},
+    graphics::Size,
+    input::{Act, Phase},
+};
+
+/// Something that happened in a recorded session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Entry {
+    /// The user took an action (only the jump and the restart are recorded).
+    Act(Act, Phase),
+    /// A new run started with the given seed.
+    Run(u64),
+    /// The player died with the given score.
+    Over(u32),
+    /// The session ended with the given score.
+    End(u32),
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Entry::Act(act, phase) => write!(f, "{} {}", act.name(), phase_name(*phase)),
+            Entry::Run(seed) => write!(f, "run {}", seed),
+            Entry::Over(score) => write!(f, "over {}", score),
+            Entry::End(score) => write!(f, "end {}", score),
+        }
+    }
+}
+
+/// Returns the name of a key phase (as written in replay files).
+fn phase_name(phase: Phase) -> &'static str {
+    match phase {
+        Phase::Press => "press",
+        Phase::Hold => "hold",
+        Phase::Release => "release",
+    }
+}
+
+/// Returns the phase with the given name (see [`phase_name`]).
+fn phase_from_name(name: &str) -> Option<Phase> {
+    [Phase::Press, Phase::Hold, Phase::Release]
+        .into_iter()
+        .find(|phase| phase_name(*phase) == name)
+}
+
+/// A recorded session: the screen size, the lives of the player, the difficulty, the game mode and
+/// the entries with the tick in which they happened.
+#[derive(Debug, Clone)]
+pub struct Replay {
+    /// Size of the screen (the stage) of the recorded session.
+    pub size: Size,
+    /// How many lives the player had (see [`Game::set_lives`]).
+    ///
+    /// [`Game::set_lives`]: crate::game::Game::set_lives
+    pub lives: u16,
+    /// Difficulty of the session and its profile (see [`Game::set_difficulty`]).
+    ///
+    /// [`Game::set_difficulty`]: crate::game::Game::set_difficulty
+    pub difficulty: (Difficulty, Profile),
+    /// Mode of the session (see [`Game::set_mode`]).
+    ///
+    /// [`Game::set_mode`]: crate::game::Game::set_mode
+    pub mode: Mode,
+    /// Entries in the order that they happened.
+    pub entries: Vec<(u64, Entry)>,
+}
+
+impl Replay {
+    // first line of every replay file
+    const HEADER: &'static str = "crabbit-replay 1";
+
+    /// Returns an empty replay of a session played on a screen of the given `size` (with a single
+    /// life, on the normal difficulty, in the endless mode).
+    pub fn new(size: Size) -> Self {
+        Replay {
+            size,
+            lives: 1,
+            difficulty: (Difficulty::Normal, Profile::default()),
+            mode: Mode::default(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Returns the tick in which the session ended.
+    pub fn length(&self) -> u64 {
+        self.entries.last().map_or(0, |(tick, _)| *tick)
+    }
+
+    /// Loads a replay from the file at `path`.
+    pub fn load(path: &Path) -> Result<Self, ReplayError> {
+        let text = fs::read_to_string(path).map_err(|e| ReplayError::Io(path.to_path_buf(), e))?;
+        Self::parse(&text).map_err(|(line, msg)| ReplayError::Parse(path.to_path_buf(), line, msg))
+    }
+
+    /// Saves the replay to the file at `path`.
+    pub fn save(&self, path: &Path) -> Result<(), ReplayError> {
+        fs::write(path, self.to_string()).map_err(|e| ReplayError::Io(path.to_path_buf(), e))
+    }
+
+    /// Parses the text of a replay file. On error returns the line number and what is wrong.
+    fn parse(text: &str) -> Result<Self, (usize, String)> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .peekable();
+
+        match lines.next() {
+            Some((_, Self::HEADER)) => (),
+            _ => return Err((1, format!("expected \"{}\"", Self::HEADER))),
+        }
+
+        let size = match lines.next() {
+            Some((n, line)) => {
+                let fields: Vec<_> = line.split_whitespace().collect();
+                match fields[..] {
+                    ["size", width, height] => Size {
+                        width: width
+                            .parse()
+                            .map_err(|_| (n, "invalid width".to_string()))?,
+                        height: height
+                            .parse()
+                            .map_err(|_| (n, "invalid height".to_string()))?,
+                    },
+                    _ => return Err((n, "expected \"size <width> <height>\"".to_string())),
+                }
+            }
+            None => return Err((2, "missing size".to_string())),
+        };
+
+        let mut replay = Replay::new(size);
+        // the lives are optional (replays recorded before the lives were added have none).
+        if let Some((n, lives)) = lines.next_if(|(_, line)| line.starts_with("lives")) {
+            replay.lives = match lives.split_whitespace().collect::<Vec<_>>()[..] {
+                ["lives", lives] => lives
+                    .parse()
+                    .map_err(|_| (n, "invalid lives".to_string()))?,
+                _ => return Err((n, "expected \"lives <number>\"".to_string())),
+            };
+        }
+        if let Some((n, line)) = lines.next_if(|(_, line)| line.starts_with("difficulty")) {
+            replay.difficulty = Self::parse_difficulty(line)
+                .ok_or((n, "expected \"difficulty <name> <profile>\"".to_string()))?;
+        }
+        // the mode is optional too (replays recorded before the modes were added are endless).
+        if let Some((n, line)) = lines.next_if(|(_, line)| line.starts_with("mode")) {
+            replay.mode = match line.split_whitespace().collect::<Vec<_>>()[..] {
+                ["mode", name] => Mode::from_name(name).ok_or((n, "invalid mode".to_string()))?,
+                _ => return Err((n, "expected \"mode <name>\"".to_string())),
+            };
+        }
+        let first = lines.peek().map_or(0, |(n, _)| *n);
+        for (n, line) in lines.filter(|(_, line)| !line.is_empty()) {
+            let entry =
+                Self::parse_entry(line).ok_or((n, format!("invalid entry \"{}\"", line)))?;
+            if replay.length() > entry.0 {
+                return Err((n, "entries out of order".to_string()));
+            }
+            replay.entries.push(entry);
+        }
+
+        match replay.entries.first() {
+            Some((0, Entry::Run(_))) => Ok(replay),
+            _ => Err((
+                first,
+                "the first entry must be \"0 run <seed>\"".to_string(),
+            )),
+        }
+    }
+
+    /// Parses a `difficulty <name> <profile>` line, where the profile is the values of its fields
+    /// in order.
+    fn parse_difficulty(line: &str) -> Option<(Difficulty, Profile)> {
+        let fields: Vec<_> = line.split_whitespace().collect();
+        match fields[..] {
+            ["difficulty", name, ini, max, step, inc, prob, min_wall, max_wall, jump] => {
+                let profile = Profile {
+                    ini_speed: ini.parse().ok()?,
+                    max_speed: max.parse().ok()?,
+                    speed_step: step.parse().ok()?,
+                    speed_inc: inc.parse().ok()?,
+                    wall_prob: prob.parse().ok()?,
+                    min_wall: min_wall.parse().ok()?,
+                    max_wall: max_wall.parse().ok()?,
+                    jump_height: jump.parse().ok()?,
+                };
+                profile.validate().ok()?;
+                Some((Difficulty::from_name(name)?, profile))
+            }
+            _ => None,
+        }
+    }
+
+    /// Parses a `<tick> <entry>` line.
+    fn parse_entry(line: &str) -> Option<(u64, Entry)> {
+        let fields: Vec<_> = line.split_whitespace().collect();
+        let (tick, fields) = fields.split_first()?;
+        let entry = match fields {
+            ["run", seed] => Entry::Run(seed.parse().ok()?),
+            ["over", score] => Entry::Over(score.parse().ok()?),
+            ["end", score] => Entry::End(score.parse().ok()?),
+            [act, phase] => {
+                let act = Act::from_name(act)?;
+                let phase = phase_from_name(phase)?;
+                Entry::Act(act, phase)
+            }
+            _ => return None,
+        };
+        Some((tick.parse().ok()?, entry))
+    }
+}
+
+impl fmt::Display for Replay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", Self::HEADER)?;
+        writeln!(f, "size {} {}", self.size.width, self.size.height)?;
+        writeln!(f, "lives {}", self.lives)?;
+        let (difficulty, p) = &self.difficulty;
+        writeln!(
+            f,
+            "difficulty {} {} {} {} {} {} {} {} {}",
+            difficulty.name(),
+            p.ini_speed,
+            p.max_speed,
+            p.speed_step,
+            p.speed_inc,
+            p.wall_prob,
+            p.min_wall,
+            p.max_wall,
+            p.jump_height
+        )?;
+        writeln!(f, "mode {}", self.mode.name())?;
+        for (tick, entry) in &self.entries {
+            writeln!(f, "{} {}", tick, entry)?;
+        }
+        Ok(())
+    }
+}
+
+/// Plays back the entries of a [`Replay`] in order.
+#[derive(Debug)]
+pub struct Playback {
+    replay: Replay,
+    next: usize,
+}
+
+impl Playback {
+    /// Returns a new `Playback` of `replay`, from the start.
+    pub fn new(replay: Replay) -> Self {
+        Playback { replay, next: 0 }
+    }
+
+    /// Returns the recorded replay.
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    /// Returns the next entry if it happened until `tick`.
+    pub fn due(&mut self, tick: u64) -> Option<Entry> {
+        match self.replay.entries.get(self.next) {
+            Some((t, entry)) if *t <= tick => {
+                self.next += 1;
+                Some(*entry)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the next entry (and its tick) without playing it.
+    pub fn peek(&self) -> Option<(u64, Entry)> {
+        self.replay.entries.get(self.next).copied()
+    }
+
+    /// Goes back to the start of the replay.
+    pub fn rewind(&mut self) {
+        self.next = 0;
+    }
+}
+
+/// A playback that didn't reproduce the recorded session.
+#[derive(Debug, Clone)]
+pub struct Divergence {
+    /// Tick in which the playback diverged.
+    pub tick: u64,
+    /// What the replay says that happened.
+    pub expected: String,
+    /// What happened instead.
+    pub found: String,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "replay diverged at tick {}: expected {}, found {}",
+            self.tick, self.expected, self.found
+        )
+    }
+}
+
+impl std::error::Error for Divergence {}
+
+/// Errors on loading or saving a [`Replay`].
+#[derive(Debug)]
+pub enum ReplayError {
+    /// Failed to read or write the file.
+    Io(PathBuf, io::Error),
+    /// The file is invalid (at the given line).
+    Parse(PathBuf, usize, String),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            Self::Parse(path, line, msg) => write!(f, "{}:{}: {}", path.display(), line, msg),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
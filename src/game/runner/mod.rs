@@ -0,0 +1,755 @@
+//! Controls the `Game` flow and handle user actions.
+
+use std::{
+    collections::VecDeque,
+    thread,
+    time::{Duration, Instant},
+};
+use termion::{
+    clear, cursor,
+    event::{Event, Key, MouseEvent},
+};
+
+use crate::{
+    components::DynComp,
+    config::Config,
+    game::{
+        autopilot::Autopilot,
+        clock::Clock,
+        headless::Controller,
+        reload::Reload,
+        replay::{Divergence, Entry, Playback, Replay},
+        Game,
+    },
+    graphics::{Pos, TermOut},
+    input::{
+        kitty::{KITTY_OFF, KITTY_ON},
+        Act, InputEvent, InputQueue, Phase, MOUSE_OFF, MOUSE_ON,
+    },
+    records::{self, Records},
+    settings::{Settings, SettingsError},
+    stats::Stats,
+};
+
+mod scene;
+use scene::{Paused, Scene, Title, Transition};
+
+// simulation tick length (milliseconds)
+pub(crate) const TICK: u64 = 60;
+// target time between two rendered frames (milliseconds)
+const FRAME: u64 = 33;
+// replay playback speeds
+const SPEEDS: [f64; 6] = [0.25, 0.5, 1.0, 2.0, 4.0, 8.0];
+// index of the normal speed in `SPEEDS`
+const NORMAL_SPEED: usize = 2;
+// how many ticks a replay seek skips (5 seconds)
+const SEEK: u64 = 5000 / TICK;
+
+/// Controls the run of a [`Game`].
+///
+/// The runner can also record the session (see [`Runner::record`]) or play back a recorded one
+/// instead of the user actions (see [`Runner::play_back`]). It runs on the terminal (see
+/// [`Runner::run`]) or without it, with scripted input (see [`Runner::run_headless`]).
+///
+/// [`Game`]: crate::game::Game
+pub struct Runner {
+    game: Game,
+    settings: Settings,
+    clock: Clock,
+    proceed: bool,
+    tick: u64,
+    recording: Option<Replay>,
+    playback: Option<Playback>,
+    speed: usize,
+    divergence: Option<Divergence>,
+    autopilot: Option<Autopilot>,
+    demo: Autopilot,
+    reports_releases: bool,
+    stats: Option<Stats>,
+    records: Option<Records>,
+    run_key: Option<records::Key>,
+    reload: Option<Reload>,
+}
+
+impl Runner {
+    /// Returns a new Runner instance.
+    pub fn new(game: Game, settings: Settings) -> Self {
+        Runner {
+            game,
+            settings,
+            clock: Clock::new(Duration::from_millis(TICK)),
+            proceed: true,
+            tick: 0,
+            recording: None,
+            playback: None,
+            speed: NORMAL_SPEED,
+            divergence: None,
+            autopilot: None,
+            demo: Autopilot::new(),
+            reports_releases: false,
+            stats: None,
+            records: None,
+            run_key: None,
+            reload: None,
+        }
+    }
+
+    /// Makes the autopilot (see [`Autopilot`]) play instead of the user, who can still pause,
+    /// restart and quit.
+    pub fn autoplay(&mut self) {
+        self.autopilot = Some(Autopilot::new());
+    }
+
+    /// Records the session, which is returned by [`Runner::take_recording`] after the run.
+    ///
+    /// The lives, the difficulty and the mode of the session are the ones of its first run (they
+    /// can still be changed on the title screen).
+    pub fn record(&mut self) {
+        self.recording = Some(Replay::new(self.game.stage.size.clone()));
+    }
+
+    /// Plays back `replay` instead of the user actions.
+    ///
+    /// While playing back, the arrow keys left and right seek backward and forward, the arrow keys
+    /// up and down change the playback speed and the restart key goes back to the start. The
+    /// playback stops at the end of the replay or as soon as the game diverges from it (see
+    /// [`Runner::divergence`]).
+    pub fn play_back(&mut self, replay: Replay) {
+        self.game.set_lives(replay.lives);
+        let (difficulty, profile) = replay.difficulty;
+        self.game.set_difficulty(difficulty, profile);
+        self.game.set_mode(replay.mode);
+        self.playback = Some(Playback::new(replay));
+    }
+
+    /// Returns the recorded session, if it was recorded.
+    pub fn take_recording(&mut self) -> Option<Replay> {
+        self.recording.take()
+    }
+
+    /// Adds the results of each run played by the user to `stats`, which are returned by
+    /// [`Runner::take_stats`] after the run.
+    pub fn track(&mut self, stats: Stats) {
+        self.stats = Some(stats);
+    }
+
+    /// Returns the tracked stats, if they were tracked.
+    pub fn take_stats(&mut self) -> Option<Stats> {
+        self.stats.take()
+    }
+
+    /// Keeps the best score of each run played by the user in `records` (see [`records::Key`]),
+    /// which are returned by [`Runner::take_records`] after the run. The record displayed by the
+    /// HUD is the one of the runs like the current one.
+    pub fn keep_records(&mut self, records: Records) {
+        self.records = Some(records);
+        self.show_record();
+    }
+
+    /// Returns the kept records, if they were kept.
+    pub fn take_records(&mut self) -> Option<Records> {
+        self.records.take()
+    }
+
+    /// Reloads `config` (the config of the game) into the game whenever it or the files it uses
+    /// change, displaying what is wrong with them if they can't be reloaded (see [`Reload`]).
+    pub fn hot_reload(&mut self, config: Config) {
+        self.reload = Some(Reload::new(config));
+    }
+
+    /// Returns how the playback diverged from the replay, if it did.
+    pub fn divergence(&self) -> Option<&Divergence> {
+        self.divergence.as_ref()
+    }
+
+    /// Returns the game.
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// Returns how many ticks of the session have been played (see [`Game::tick`]).
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Runs the game.
+    ///
+    /// The game is simulated in fixed ticks (see [`Game::tick`]) while rendering happens at its own
+    /// frame rate, so the game speed doesn't depend on how long it takes to draw a frame.
+    ///
+    /// What is displayed and how the input is handled depends on the scene (e.g. the title, the
+    /// game being played or the pause screen) on the top of a stack of scenes. The scenes below it
+    /// are still rendered (e.g. the game behind the pause screen) but they don't change.
+    pub fn run(&mut self, out: &mut TermOut) {
+        let input = InputQueue::spawn();
+        let next_frame = Instant::now();
+        self.drive(out, Drive::Terminal { input, next_frame });
+    }
+
+    /// Runs the game like [`Runner::run`], but without a terminal and as fast as possible, for (at
+    /// most) the given amount of `ticks` of the clock.
+    ///
+    /// The user input comes from `input` and every tick is rendered to `out` (e.g. a `Vec<u8>`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use termion::event::Key;
+    /// use crabbit::{
+    ///     config::Config,
+    ///     game::{runner::ScriptedInput, Runner},
+    ///     settings::Settings,
+    /// };
+    ///
+    /// let mut game = Config::builtin().unwrap().build(100, 30).unwrap();
+    /// game.set_seed(Some(42));
+    /// let mut runner = Runner::new(game, Settings::default());
+    ///
+    /// // starts a run from the title screen, plays 20 ticks and quits
+    /// let input = ScriptedInput::new()
+    ///     .press(0, Key::Char('\n'))
+    ///     .press(20, Key::Char('q'));
+    /// let mut out = Vec::new();
+    /// runner.run_headless(&mut out, input, 1000);
+    /// assert_eq!(runner.tick(), 20);
+    /// assert!(!out.is_empty());
+    /// ```
+    pub fn run_headless(&mut self, out: &mut TermOut, input: ScriptedInput, ticks: u64) {
+        self.drive(out, Drive::Script { input, left: ticks });
+    }
+
+    /// Runs the game (see [`Runner::run`]) as `drive` tells.
+    fn drive(&mut self, out: &mut TermOut, mut drive: Drive) {
+        // the terminal modes stay the same until the end, even if the settings change
+        let (mouse, kitty_keyboard) = (self.settings.mouse, self.settings.kitty_keyboard);
+
+        write!(out, "{}{}", clear::All, cursor::Hide).unwrap();
+        if mouse {
+            write!(out, "{}", MOUSE_ON).unwrap();
+        }
+        if kitty_keyboard {
+            write!(out, "{}", KITTY_ON).unwrap();
+        }
+
+        let mut scenes: Vec<Box<dyn Scene>> = vec![Box::new(Title::new(self))];
+        scenes[0].enter(self);
+        while self.proceed && drive.goes_on() {
+            let Some(scene) = scenes.last_mut() else {
+                break;
+            };
+
+            // every pending event is handled before the update, unless some of them changes the
+            // scene (the remaining events are then handled by the next scene).
+            self.reports_releases = drive.reports_releases();
+            let mut transition = Transition::None;
+            while let Some(ev) = drive.pop() {
+                transition = scene.handle(self, &ev);
+                if !matches!(transition, Transition::None) || !self.proceed {
+                    break;
+                }
+            }
+            if matches!(transition, Transition::None) {
+                let ticks = match drive {
+                    Drive::Terminal { .. } => self.clock.ticks(),
+                    Drive::Script { .. } => 1,
+                };
+                transition = scene.update(self, ticks);
+            }
+
+            let reloaded = match &mut self.reload {
+                Some(reload) => reload.poll(&mut self.game),
+                None => false,
+            };
+            if self.switch(&mut scenes, transition) || reloaded {
+                // the previous scene (or look) is cleared from the screen, the next one is drawn
+                // right away.
+                write!(out, "{}", clear::All).unwrap();
+                if let Drive::Terminal { next_frame, .. } = &mut drive {
+                    *next_frame = Instant::now();
+                }
+            }
+            self.present(&scenes, out, &mut drive);
+        }
+        if let Drive::Terminal { input, .. } = &mut drive {
+            input.shutdown();
+        }
+        self.keep_record();
+        let score = self.game.hud.score().current();
+        if let Some(replay) = &mut self.recording {
+            replay.entries.push((self.tick, Entry::End(score)));
+        }
+        if mouse {
+            write!(out, "{}", MOUSE_OFF).unwrap();
+        }
+        if kitty_keyboard {
+            write!(out, "{}", KITTY_OFF).unwrap();
+        }
+        write!(out, "{}{}\r", cursor::Show, clear::All).unwrap();
+        out.flush().unwrap();
+    }
+
+    /// Changes the stack of `scenes` as told by `transition`. Returns `true` if it has changed.
+    ///
+    /// The scene that ends up on the top of the stack is entered (see [`Scene::enter`]).
+    fn switch(&mut self, scenes: &mut Vec<Box<dyn Scene>>, transition: Transition) -> bool {
+        match transition {
+            Transition::None => return false,
+            Transition::Push(scene) => scenes.push(scene),
+            Transition::Pop => {
+                scenes.pop();
+            }
+            Transition::Replace(new) => {
+                scenes.pop();
+                scenes.extend(new);
+            }
+        }
+        if let Some(scene) = scenes.last_mut() {
+            scene.enter(self);
+        }
+        true
+    }
+
+    /// Renders the `scenes` (from the bottom to the top of the stack) if it is time for the next
+    /// frame, then sleeps until the next tick or frame. A scripted run renders every tick and
+    /// doesn't sleep.
+    fn present(&mut self, scenes: &[Box<dyn Scene>], out: &mut TermOut, drive: &mut Drive) {
+        let render = |out: &mut TermOut| {
+            scenes.iter().for_each(|scene| scene.render(self, out));
+            out.flush().unwrap();
+        };
+        match drive {
+            Drive::Terminal { next_frame, .. } => {
+                let now = Instant::now();
+                if now >= *next_frame {
+                    render(out);
+                    *next_frame = now + Duration::from_millis(FRAME);
+                }
+
+                let until_frame = next_frame.saturating_duration_since(Instant::now());
+                thread::sleep(self.clock.until_tick().min(until_frame));
+            }
+            Drive::Script { input, left } => {
+                render(out);
+                input.tick += 1;
+                *left -= 1;
+            }
+        }
+    }
+
+    /// Advances the demo played by the autopilot behind the title screen by `ticks` ticks.
+    ///
+    /// The demo isn't recorded and its score doesn't count for the record.
+    fn demo(&mut self, ticks: u32) {
+        for _ in 0..ticks {
+            if let Some((_, phase)) = self.demo.act(self.tick, &self.game) {
+                self.game.jump(phase);
+            }
+            if self.game.tick() {
+                self.game.hud.score_mut().discard();
+                self.game.reset();
+                self.game.update();
+                break;
+            }
+        }
+    }
+
+    /// Returns the user action triggered by an input event, if any.
+    fn act(&self, event: &Event) -> Option<Act> {
+        match event {
+            Event::Key(key) => self.settings.keys.act(*key),
+            Event::Mouse(MouseEvent::Press(_, col, row)) if self.settings.mouse => {
+                Some(Act::Click(Pos {
+                    col: *col,
+                    row: *row,
+                }))
+            }
+            _ => None,
+        }
+    }
+
+    /// Handle user actions while the game is played. Returns the scene change that the action
+    /// triggers (e.g. the pause).
+    fn act_handler(&mut self, act: Act) -> Transition {
+        match act {
+            // the player is controlled by the replay during a playback (or by the autopilot).
+            Act::PlayerJump if self.playback.is_some() || self.autopilot.is_some() => (),
+            Act::PlayerJump => self.apply(Act::PlayerJump, Phase::Press),
+            Act::Pause => return Transition::Push(Box::new(Paused::new(self))),
+            Act::Restart if self.playback.is_some() => self.seek(0),
+            Act::Restart => self.apply(Act::Restart, Phase::Press),
+            Act::Quit => self.quit(),
+            Act::Settings => return Transition::Push(Box::new(scene::Controls)),
+            // a click makes the player jump.
+            Act::Click(_) => return self.act_handler(Act::PlayerJump),
+        }
+        Transition::None
+    }
+
+    /// Handle the phases of the jump action (see [`Game::jump`]).
+    fn jump_handler(&mut self, phase: Phase) {
+        if self.autopilot.is_some() {
+            return;
+        }
+        // if key releases aren't reported, a key press can't be told apart from a tap, otherwise
+        // the key is held until its release.
+        let phase = match phase {
+            Phase::Press if self.reports_releases => Phase::Hold,
+            phase => phase,
+        };
+        self.apply(Act::PlayerJump, phase);
+    }
+
+    /// Applies an action that changes the game (see [`Entry::Act`]), recording it if the session
+    /// is being recorded.
+    fn apply(&mut self, act: Act, phase: Phase) {
+        if let Some(replay) = &mut self.recording {
+            replay.entries.push((self.tick, Entry::Act(act, phase)));
+        }
+
+        match act {
+            Act::PlayerJump => self.game.jump(phase),
+            Act::Restart => self.restart(),
+            _ => (),
+        }
+    }
+
+    /// Advances the game by one tick, playing back the replay entries due until then. Returns
+    /// `true` if the player has died (the next run starts on [`Runner::restart`]).
+    fn step(&mut self) -> bool {
+        while let Some(entry) = self.playback.as_mut().and_then(|p| p.due(self.tick)) {
+            let score = self.game.hud.score().current();
+            match entry {
+                Entry::Act(act, phase) => self.apply(act, phase),
+                Entry::End(_) => {
+                    self.expect(entry, Entry::End(score));
+                    self.quit();
+                    return false;
+                }
+                Entry::Over(_) => {
+                    self.diverge(
+                        entry.to_string(),
+                        format!("player alive with score {}", score),
+                    );
+                    return false;
+                }
+                Entry::Run(_) => {
+                    self.diverge(entry.to_string(), "no restart".to_string());
+                    return false;
+                }
+            }
+        }
+
+        if self.playback.is_none() {
+            if let Some((act, phase)) = self
+                .autopilot
+                .as_mut()
+                .and_then(|a| a.act(self.tick, &self.game))
+            {
+                self.apply(act, phase);
+            }
+        }
+
+        let counts = self.counts();
+        let hit = match (&mut self.stats, counts) {
+            (Some(stats), true) => self.game.tick_with(&mut [stats]),
+            _ => self.game.tick(),
+        };
+        self.tick += 1;
+        if hit {
+            let over = Entry::Over(self.game.hud.score().current());
+            if let Some(replay) = &mut self.recording {
+                replay.entries.push((self.tick, over));
+            }
+            if let Some(expected) = self.playback.as_mut().map(|p| p.due(self.tick)) {
+                match expected {
+                    Some(entry) => self.expect(entry, over),
+                    None => self.diverge(self.next_entry(), over.to_string()),
+                }
+            }
+        }
+        hit
+    }
+
+    /// Returns `true` if the runs count for the statistics: they are played by the user (not
+    /// played back nor by the autopilot) in a ranked mode (see [`Mode::is_ranked`]).
+    ///
+    /// [`Mode::is_ranked`]: crate::game::Mode::is_ranked
+    fn counts(&self) -> bool {
+        self.playback.is_none() && self.autopilot.is_none() && self.game.mode().is_ranked()
+    }
+
+    /// Stops the playback if the `found` entry isn't the `expected` one.
+    fn expect(&mut self, expected: Entry, found: Entry) {
+        if expected != found {
+            self.diverge(expected.to_string(), found.to_string());
+        }
+    }
+
+    /// Stops the playback, reporting that `found` happened instead of `expected`.
+    fn diverge(&mut self, expected: String, found: String) {
+        if self.divergence.is_none() {
+            self.divergence = Some(Divergence {
+                tick: self.tick,
+                expected,
+                found,
+            });
+        }
+        self.quit();
+    }
+
+    /// Describes the next entry of the playback.
+    fn next_entry(&self) -> String {
+        match self.playback.as_ref().and_then(|p| p.peek()) {
+            Some((tick, entry)) => format!("{} at tick {}", entry, tick),
+            None => "the end of the replay".to_string(),
+        }
+    }
+
+    /// Handle the input during a playback: the replay controls and the pause and quit actions.
+    fn replay_handler(&mut self, ev: &InputEvent) -> Transition {
+        if ev.phase == Phase::Release {
+            return Transition::None;
+        }
+
+        let speed = self.speed;
+        match ev.event {
+            Event::Key(Key::Left) => self.seek(self.tick.saturating_sub(SEEK)),
+            Event::Key(Key::Right) => self.seek(self.tick + SEEK),
+            Event::Key(Key::Up) => self.set_speed((speed + 1).min(SPEEDS.len() - 1)),
+            Event::Key(Key::Down) => self.set_speed(speed.saturating_sub(1)),
+            ref event if ev.phase == Phase::Press => {
+                if let Some(act) = self.act(event) {
+                    return self.act_handler(act);
+                }
+            }
+            _ => (),
+        }
+        Transition::None
+    }
+
+    /// Changes the playback speed to `SPEEDS[speed]`.
+    fn set_speed(&mut self, speed: usize) {
+        self.speed = speed;
+        let tick = Duration::from_millis(TICK).div_f64(SPEEDS[speed]);
+        self.clock.set_tick(tick);
+    }
+
+    /// Moves the playback to the given tick (at most the end of the replay).
+    ///
+    /// Seeking backward replays the session from the start.
+    fn seek(&mut self, tick: u64) {
+        let Some(playback) = &mut self.playback else {
+            return;
+        };
+        let tick = tick.min(playback.replay().length());
+        if tick < self.tick {
+            playback.rewind();
+            self.tick = 0;
+            self.restart();
+        }
+        while self.tick < tick && self.proceed {
+            if self.step() {
+                self.restart();
+            }
+        }
+        self.clock.resume();
+    }
+
+    /// Returns the playback status displayed by the HUD.
+    fn replay_status(&self) -> String {
+        let secs = |ticks: u64| (ticks * TICK) as f64 / 1000.0;
+        let length = self.playback.as_ref().map_or(0, |p| p.replay().length());
+        format!(
+            "Replay x{:<4} {:>6.1}s / {:.1}s  (left/right: seek, up/down: speed)",
+            SPEEDS[self.speed],
+            secs(self.tick),
+            secs(length)
+        )
+    }
+
+    /// Handle keys pressed on the controls screen. The new key bindings are saved when the screen
+    /// is closed.
+    fn controls_handler(&mut self, key: Key) {
+        if let Some(keys) = self.game.hud.controls_mut().handle(key) {
+            self.settings.keys = keys;
+            if let Err(e) = self.settings.save() {
+                let controls = self.game.hud.controls_mut();
+                controls.open(self.settings.keys.clone());
+                controls.set_message(format!("Failed to save: {}", e));
+            }
+        }
+    }
+
+    /// Handle keys pressed on the settings screen. The new settings are saved when the screen is
+    /// closed.
+    fn preferences_handler(&mut self, key: Key) {
+        let Some(settings) = self.game.hud.preferences_mut().handle(key) else {
+            return;
+        };
+        if let Err(e) = self.apply_settings(settings) {
+            let preferences = self.game.hud.preferences_mut();
+            preferences.open(self.settings.clone());
+            preferences.set_message(format!("Failed to save: {}", e));
+        }
+    }
+
+    /// Changes and saves the settings. The lives and the difficulty apply from the next run on
+    /// (unless the session is played back or its recording has started, since a replay keeps the
+    /// ones it started with).
+    fn apply_settings(&mut self, settings: Settings) -> Result<(), SettingsError> {
+        let recorded = self
+            .recording
+            .as_ref()
+            .is_some_and(|r| !r.entries.is_empty());
+        if !recorded && self.playback.is_none() {
+            let difficulty = settings.difficulty;
+            self.game.set_lives(settings.lives);
+            self.game
+                .set_difficulty(difficulty, settings.profile(difficulty));
+        }
+        self.settings = settings;
+        self.settings.save()
+    }
+
+    /// Restart the game.
+    ///
+    /// The seed of the new run is recorded or, during a playback, taken from the replay (which
+    /// may end instead, if the session ended after the death of the player).
+    fn restart(&mut self) {
+        let score = self.game.hud.score().current();
+        self.keep_record();
+        self.game.reset();
+        self.run_key = Some(Self::key(&self.game));
+        self.show_record();
+        if let Some(playback) = &mut self.playback {
+            match playback.due(self.tick) {
+                Some(Entry::Run(seed)) => self.game.set_seed(Some(seed)),
+                Some(entry @ Entry::End(_)) => {
+                    self.expect(entry, Entry::End(score));
+                    self.quit();
+                }
+                Some(entry) => self.diverge(entry.to_string(), "a restart".to_string()),
+                None => self.diverge(self.next_entry(), "a restart".to_string()),
+            }
+        }
+        self.game.update();
+        if let Some(replay) = &mut self.recording {
+            if replay.entries.is_empty() {
+                replay.lives = self.game.lives();
+                replay.difficulty = (self.game.difficulty(), *self.game.difficulty_profile());
+                replay.mode = self.game.mode();
+            }
+            replay
+                .entries
+                .push((self.tick, Entry::Run(self.game.seed())));
+        }
+    }
+
+    /// Returns the key of the record of the current run of `game`.
+    fn key(game: &Game) -> records::Key {
+        records::Key::new(game.mode(), game.difficulty(), game.is_seeded())
+    }
+
+    /// Keeps the score of the current run as the record of its key, if it beats it and the run
+    /// counts (see [`Runner::counts`]). The demo played before the first run doesn't count.
+    fn keep_record(&mut self) {
+        let score = self.game.hud.score().current();
+        let counts = self.counts();
+        if let (Some(records), Some(key), true) = (&mut self.records, self.run_key, counts) {
+            records.set(key, score);
+        }
+    }
+
+    /// Displays the record of the current run (or of the first one, before it starts) in the
+    /// HUD. The record of a run that doesn't count is left as it is.
+    fn show_record(&mut self) {
+        let key = self.run_key.unwrap_or_else(|| Self::key(&self.game));
+        let counts = self.counts();
+        if let (Some(records), true) = (&self.records, counts) {
+            let record = records.get(key);
+            self.game.hud.score_mut().set_record(record);
+        }
+    }
+
+    /// Quit the game.
+    fn quit(&mut self) {
+        self.proceed = false;
+    }
+}
+
+/// What drives a run of the [`Runner`]: where the input comes from and how the time goes by.
+enum Drive {
+    /// The terminal: the input of the user and the real time.
+    Terminal {
+        input: InputQueue,
+        next_frame: Instant,
+    },
+    /// A script: the scripted input and a tick of the clock per loop, for `left` more ticks.
+    Script { input: ScriptedInput, left: u64 },
+}
+
+impl Drive {
+    /// Returns `true` if the run can go on.
+    fn goes_on(&self) -> bool {
+        match self {
+            Self::Terminal { .. } => true,
+            Self::Script { left, .. } => *left > 0,
+        }
+    }
+
+    /// Returns the oldest pending input event, if any.
+    fn pop(&mut self) -> Option<InputEvent> {
+        match self {
+            Self::Terminal { input, .. } => input.pop(),
+            Self::Script { input, .. } => input.pop(),
+        }
+    }
+
+    /// Returns `true` if the key releases are reported (see [`InputQueue::reports_releases`]).
+    fn reports_releases(&self) -> bool {
+        match self {
+            Self::Terminal { input, .. } => input.reports_releases(),
+            Self::Script { input, .. } => input.releases,
+        }
+    }
+}
+
+/// The user input of a headless run (see [`Runner::run_headless`]): the events handled before
+/// each tick of the clock, counting from 0.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptedInput {
+    events: VecDeque<(u64, InputEvent)>,
+    releases: bool,
+    tick: u64,
+}
+
+impl ScriptedInput {
+    /// Returns a new `ScriptedInput` without events.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an `event` in the given `phase`, handled before `tick`. The events must be added in
+    /// the order of their ticks.
+    ///
+    /// Like on a terminal that reports them, the key releases are reported (see
+    /// [`InputQueue::reports_releases`]) once an event has another phase than [`Phase::Press`].
+    pub fn event(mut self, tick: u64, event: Event, phase: Phase) -> Self {
+        self.releases |= phase != Phase::Press;
+        self.events.push_back((tick, InputEvent { event, phase }));
+        self
+    }
+
+    /// Adds a press of `key`, handled before `tick`.
+    pub fn press(self, tick: u64, key: Key) -> Self {
+        self.event(tick, Event::Key(key), Phase::Press)
+    }
+
+    /// Returns the oldest event due by the current tick, if any.
+    fn pop(&mut self) -> Option<InputEvent> {
+        match self.events.front() {
+            Some((tick, _)) if *tick <= self.tick => self.events.pop_front().map(|(_, ev)| ev),
+            _ => None,
+        }
+    }
+}
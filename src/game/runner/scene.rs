@@ -0,0 +1,494 @@
+//! The scenes of the game: what is displayed and how the user input is handled at each moment.
+
+use termion::event::{Event, Key};
+
+use super::{Runner, TICK};
+use crate::{
+    components::{
+        hud::{Menu, Summary},
+        DynComp,
+    },
+    game::{Difficulty, Mode},
+    graphics::{Render, TermOut},
+    input::{Act, InputEvent, Phase},
+};
+
+/// How the stack of scenes changes.
+pub enum Transition {
+    /// The scene stays.
+    None,
+    /// Puts a scene on top of the current one.
+    Push(Box<dyn Scene>),
+    /// Removes the current scene, uncovering the one below it.
+    Pop,
+    /// Replaces the current scene with the given scenes (the last one on top).
+    Replace(Vec<Box<dyn Scene>>),
+}
+
+/// A screen of the game that handles its own input, update and render.
+///
+/// The scenes are stacked and only the one on the top is entered, handles the input and is
+/// updated, but all of them are rendered (from the bottom to the top), so a scene can be displayed
+/// over another (e.g. the pause screen over the game).
+pub trait Scene {
+    /// Called when the scene gets to the top of the stack (when pushed or uncovered).
+    fn enter(&mut self, _runner: &mut Runner) {}
+
+    /// Handles an input event.
+    fn handle(&mut self, _runner: &mut Runner, _ev: &InputEvent) -> Transition {
+        Transition::None
+    }
+
+    /// Advances the scene by the given amount of ticks.
+    fn update(&mut self, _runner: &mut Runner, _ticks: u32) -> Transition {
+        Transition::None
+    }
+
+    /// Renders the scene.
+    fn render(&self, runner: &Runner, out: &mut TermOut);
+}
+
+/// An entry of the main menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Start {
+    Play(Mode),
+    Difficulty,
+    Settings,
+    Statistics,
+    Quit,
+}
+
+/// The title screen: the main menu, behind which the autopilot plays a demo.
+///
+/// The menu starts a run in each game mode (see [`Mode`]), changes the difficulty (with `Enter`
+/// or the arrow keys left and right) and the settings, and shows the statistics. During a
+/// playback it only starts watching the replay, which has its own mode and difficulty.
+pub struct Title {
+    menu: Menu<Start>,
+}
+
+impl Title {
+    /// Returns a new `Title` screen with the entry of the current mode selected.
+    pub fn new(runner: &Runner) -> Self {
+        Self::at(runner, Start::Play(runner.game.mode()))
+    }
+
+    /// Returns a new `Title` screen with the entry of `choice` selected.
+    pub fn at(runner: &Runner, choice: Start) -> Self {
+        let mut menu = Menu::new(&runner.game.stage.size);
+        if runner.playback.is_some() {
+            menu = menu.entry("Watch replay", Start::Play(runner.game.mode()));
+        } else {
+            for mode in Mode::ALL {
+                menu = menu.entry(&mode.to_string(), Start::Play(mode));
+            }
+            menu = menu
+                .entry(&Self::difficulty_label(runner), Start::Difficulty)
+                .entry("Settings", Start::Settings)
+                .entry("Statistics", Start::Statistics);
+        }
+        let mut menu = menu.confirmed("Quit", Start::Quit, "Quit the game?");
+        menu.select(choice);
+        Title { menu }
+    }
+
+    /// Returns the label of the difficulty entry.
+    fn difficulty_label(runner: &Runner) -> String {
+        format!("Difficulty: {}", runner.game.difficulty())
+    }
+
+    /// Changes the difficulty to the next (or previous, if not `forward`) one and saves it.
+    fn change_difficulty(&mut self, runner: &mut Runner, forward: bool) {
+        let all = Difficulty::ALL;
+        let i = all
+            .iter()
+            .position(|d| *d == runner.game.difficulty())
+            .unwrap_or(0);
+        let next = if forward { i + 1 } else { i + all.len() - 1 };
+        let mut settings = runner.settings.clone();
+        settings.difficulty = all[next % all.len()];
+        if let Err(e) = runner.apply_settings(settings) {
+            runner.game.hud.toast(format!("Failed to save: {}", e));
+        }
+        let label = Self::difficulty_label(runner);
+        self.menu.relabel(Start::Difficulty, &label);
+    }
+}
+
+impl Scene for Title {
+    fn handle(&mut self, runner: &mut Runner, ev: &InputEvent) -> Transition {
+        if ev.phase != Phase::Press {
+            return Transition::None;
+        }
+
+        let on_difficulty = self.menu.selected() == Start::Difficulty;
+        let choice = match (&ev.event, runner.act(&ev.event)) {
+            (Event::Key(key), _) if self.menu.is_asking() => self.menu.handle(*key),
+            (Event::Key(key @ (Key::Left | Key::Right)), _) if on_difficulty => {
+                self.change_difficulty(runner, *key == Key::Right);
+                None
+            }
+            (Event::Key(key @ (Key::Up | Key::Down | Key::Char('\n'))), _) => {
+                self.menu.handle(*key)
+            }
+            (_, Some(Act::Click(pos))) => self.menu.click(&pos),
+            (_, Some(Act::Quit)) => {
+                self.menu.select(Start::Quit);
+                self.menu.handle(Key::Char('\n'))
+            }
+            _ => None,
+        };
+        match choice {
+            Some(Start::Play(mode)) => {
+                runner.game.hud.score_mut().discard();
+                runner.game.set_mode(mode);
+                runner.restart();
+                Transition::Replace(vec![Box::new(Playing)])
+            }
+            Some(Start::Difficulty) => {
+                self.change_difficulty(runner, true);
+                Transition::None
+            }
+            Some(Start::Settings) => Transition::Replace(vec![Box::new(Preferences::Title)]),
+            Some(Start::Statistics) => Transition::Replace(vec![Box::new(Statistics)]),
+            Some(Start::Quit) => {
+                runner.quit();
+                Transition::None
+            }
+            None => Transition::None,
+        }
+    }
+
+    fn update(&mut self, runner: &mut Runner, ticks: u32) -> Transition {
+        runner.demo(ticks);
+        Transition::None
+    }
+
+    fn render(&self, runner: &Runner, out: &mut TermOut) {
+        runner.game.render(out);
+        runner.game.hud.splash().render_title(out);
+        self.menu.render(out);
+    }
+}
+
+/// The statistics screen, over the demo: the statistics of the runs played by the user (see
+/// [`Stats`]). Any key (or click) goes back to the main menu.
+///
+/// [`Stats`]: crate::stats::Stats
+pub struct Statistics;
+
+impl Statistics {
+    // screen title
+    const TITLE: &'static str = "Statistics";
+    // shown when the statistics aren't tracked
+    const UNTRACKED: &'static str = "No statistics are kept for this session.";
+    // usage help
+    const HELP: &'static str = "Press any key to go back.";
+}
+
+impl Scene for Statistics {
+    fn handle(&mut self, runner: &mut Runner, ev: &InputEvent) -> Transition {
+        if ev.phase != Phase::Press {
+            return Transition::None;
+        }
+        let title = Title::at(runner, Start::Statistics);
+        Transition::Replace(vec![Box::new(title)])
+    }
+
+    fn update(&mut self, runner: &mut Runner, ticks: u32) -> Transition {
+        runner.demo(ticks);
+        Transition::None
+    }
+
+    fn render(&self, runner: &Runner, out: &mut TermOut) {
+        runner.game.render(out);
+        let mut lines = vec![Self::TITLE.to_string(), String::new()];
+        match &runner.stats {
+            Some(stats) => lines.extend(stats.to_string().lines().map(str::to_string)),
+            None => lines.push(Self::UNTRACKED.to_string()),
+        }
+        lines.push(String::new());
+        lines.push(Self::HELP.to_string());
+        runner.game.hud.splash().render_lines(out, &lines);
+    }
+}
+
+/// The game being played.
+pub struct Playing;
+
+impl Scene for Playing {
+    fn enter(&mut self, runner: &mut Runner) {
+        runner.clock.resume();
+    }
+
+    fn handle(&mut self, runner: &mut Runner, ev: &InputEvent) -> Transition {
+        match (runner.act(&ev.event), ev.phase) {
+            _ if runner.playback.is_some() => runner.replay_handler(ev),
+            (Some(Act::PlayerJump), phase) => {
+                runner.jump_handler(phase);
+                Transition::None
+            }
+            (Some(act), Phase::Press) => runner.act_handler(act),
+            _ => Transition::None,
+        }
+    }
+
+    fn update(&mut self, runner: &mut Runner, ticks: u32) -> Transition {
+        for _ in 0..ticks {
+            if runner.step() {
+                let over = GameOver::new(runner);
+                let counts = runner.counts();
+                if let (Some(stats), true) = (&mut runner.stats, counts) {
+                    stats.add(&over.summary);
+                }
+                return Transition::Push(Box::new(over));
+            }
+            if !runner.proceed {
+                break;
+            }
+        }
+        if runner.playback.is_some() {
+            let status = runner.replay_status();
+            runner.game.hud.set_status(status);
+        }
+        Transition::None
+    }
+
+    fn render(&self, runner: &Runner, out: &mut TermOut) {
+        runner.game.render(out);
+    }
+}
+
+/// An entry of the pause menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Choice {
+    Resume,
+    Restart,
+    Settings,
+    Controls,
+    Quit,
+}
+
+/// The pause screen: a menu over the frozen game, whose background keeps moving.
+///
+/// The entries are chosen with the arrow keys and `Enter` (or clicked), restarting and quitting
+/// ask for confirmation. The pause key resumes the game and the keys of the other actions choose
+/// their entries.
+pub struct Paused {
+    menu: Menu<Choice>,
+}
+
+impl Paused {
+    /// Returns a new `Paused` screen with the `Resume` entry selected.
+    pub fn new(runner: &Runner) -> Self {
+        Self::at(runner, Choice::Resume)
+    }
+
+    /// Returns a new `Paused` screen with the entry of `choice` selected.
+    pub fn at(runner: &Runner, choice: Choice) -> Self {
+        let mut menu = Menu::new(&runner.game.stage.size)
+            .entry("Resume", Choice::Resume)
+            .confirmed("Restart", Choice::Restart, "Restart the run?")
+            .entry("Settings", Choice::Settings)
+            .entry("Controls", Choice::Controls)
+            .confirmed("Quit", Choice::Quit, "Quit the game?");
+        menu.select(choice);
+        Paused { menu }
+    }
+
+    /// Asks for the confirmation of `choice` (or takes it, if it needs none).
+    fn ask(&mut self, choice: Choice) -> Option<Choice> {
+        self.menu.select(choice);
+        self.menu.handle(Key::Char('\n'))
+    }
+}
+
+impl Scene for Paused {
+    fn handle(&mut self, runner: &mut Runner, ev: &InputEvent) -> Transition {
+        if ev.phase != Phase::Press {
+            return Transition::None;
+        }
+
+        // the menu keys come before the key bindings (e.g. `Enter` and `Up` also jump).
+        let choice = match (&ev.event, runner.act(&ev.event)) {
+            (Event::Key(key), _) if self.menu.is_asking() => self.menu.handle(*key),
+            (Event::Key(key @ (Key::Up | Key::Down | Key::Char('\n'))), _) => {
+                self.menu.handle(*key)
+            }
+            (_, Some(Act::Click(pos))) => self.menu.click(&pos),
+            (_, Some(Act::Pause)) => Some(Choice::Resume),
+            (_, Some(Act::Restart)) => self.ask(Choice::Restart),
+            (_, Some(Act::Quit)) => self.ask(Choice::Quit),
+            (_, Some(Act::Settings)) => Some(Choice::Controls),
+            _ => None,
+        };
+        match choice {
+            Some(Choice::Resume) => Transition::Pop,
+            Some(Choice::Restart) => {
+                runner.act_handler(Act::Restart);
+                Transition::Pop
+            }
+            Some(Choice::Settings) => Transition::Replace(vec![Box::new(Preferences::Paused)]),
+            Some(Choice::Controls) => Transition::Replace(vec![Box::new(Controls)]),
+            Some(Choice::Quit) => {
+                runner.quit();
+                Transition::None
+            }
+            None => Transition::None,
+        }
+    }
+
+    fn update(&mut self, runner: &mut Runner, ticks: u32) -> Transition {
+        for _ in 0..ticks {
+            runner.game.stage.scroll_background();
+        }
+        Transition::None
+    }
+
+    fn render(&self, runner: &Runner, out: &mut TermOut) {
+        runner.game.hud.splash().render_pause(out);
+        self.menu.render(out);
+    }
+}
+
+/// The death of the player: the game freezes while the death sequence of the player plays (see
+/// [`Player::kill`]), then the results of the run are displayed.
+///
+/// Any key (or click) starts the next run, which starts by itself when the player isn't
+/// controlled by the user.
+///
+/// [`Player::kill`]: crate::components::Player::kill
+pub struct GameOver {
+    summary: Summary,
+    waited: u32,
+}
+
+impl GameOver {
+    // how many ticks the results are displayed before the next run can start (1 second)
+    const DELAY: u32 = (1000 / TICK) as u32;
+
+    /// Returns a new `GameOver` of the current run.
+    pub fn new(runner: &Runner) -> Self {
+        GameOver {
+            summary: runner.game.summary(),
+            waited: 0,
+        }
+    }
+
+    /// Starts the next run.
+    fn next_run(runner: &mut Runner) -> Transition {
+        runner.restart();
+        Transition::Pop
+    }
+}
+
+impl Scene for GameOver {
+    fn handle(&mut self, runner: &mut Runner, ev: &InputEvent) -> Transition {
+        match runner.act(&ev.event) {
+            _ if ev.phase != Phase::Press => Transition::None,
+            Some(Act::Quit) => {
+                runner.quit();
+                Transition::None
+            }
+            _ if self.waited >= Self::DELAY => Self::next_run(runner),
+            _ => Transition::None,
+        }
+    }
+
+    fn update(&mut self, runner: &mut Runner, ticks: u32) -> Transition {
+        for _ in 0..ticks {
+            if runner.game.player.is_dead() {
+                self.waited = self.waited.saturating_add(1);
+            } else {
+                runner.game.player.update();
+            }
+        }
+        let controlled = runner.playback.is_some() || runner.autopilot.is_some();
+        if controlled && self.waited >= Self::DELAY {
+            return Self::next_run(runner);
+        }
+        Transition::None
+    }
+
+    fn render(&self, runner: &Runner, out: &mut TermOut) {
+        if runner.game.player.is_dead() {
+            runner
+                .game
+                .hud
+                .splash()
+                .render_game_over(out, &self.summary);
+        }
+    }
+}
+
+/// The controls screen, to edit the key bindings. The game is paused when the screen is left.
+pub struct Controls;
+
+impl Scene for Controls {
+    fn enter(&mut self, runner: &mut Runner) {
+        let keys = runner.settings.keys.clone();
+        runner.game.hud.controls_mut().open(keys);
+    }
+
+    fn handle(&mut self, runner: &mut Runner, ev: &InputEvent) -> Transition {
+        if let (Event::Key(key), Phase::Press) = (&ev.event, ev.phase) {
+            runner.controls_handler(*key);
+        }
+        if runner.game.hud.controls().is_open() {
+            Transition::None
+        } else {
+            Transition::Replace(vec![Box::new(Paused::at(runner, Choice::Controls))])
+        }
+    }
+
+    fn render(&self, runner: &Runner, out: &mut TermOut) {
+        runner.game.hud.controls().render(out);
+    }
+}
+
+/// The settings screen, to edit the other settings. The screen goes back to where it was opened
+/// from: the main menu or the pause screen.
+pub enum Preferences {
+    Title,
+    Paused,
+}
+
+impl Scene for Preferences {
+    fn enter(&mut self, runner: &mut Runner) {
+        let settings = runner.settings.clone();
+        runner.game.hud.preferences_mut().open(settings);
+    }
+
+    fn handle(&mut self, runner: &mut Runner, ev: &InputEvent) -> Transition {
+        if let (Event::Key(key), Phase::Press) = (&ev.event, ev.phase) {
+            runner.preferences_handler(*key);
+        }
+        if runner.game.hud.preferences().is_open() {
+            return Transition::None;
+        }
+        match self {
+            Preferences::Title => {
+                let title = Title::at(runner, Start::Settings);
+                Transition::Replace(vec![Box::new(title)])
+            }
+            Preferences::Paused => {
+                let paused = Paused::at(runner, Choice::Settings);
+                Transition::Replace(vec![Box::new(paused)])
+            }
+        }
+    }
+
+    fn update(&mut self, runner: &mut Runner, ticks: u32) -> Transition {
+        if let Preferences::Title = self {
+            runner.demo(ticks);
+        }
+        Transition::None
+    }
+
+    fn render(&self, runner: &Runner, out: &mut TermOut) {
+        if let Preferences::Title = self {
+            runner.game.render(out);
+        }
+        runner.game.hud.preferences().render(out);
+    }
+}
@@ -0,0 +1,168 @@
+//! An off-screen terminal screen.
+
+use std::{fmt, io, slice::Chunks};
+
+use super::{Pos, Size};
+
+/// State of the escape sequence being parsed.
+#[derive(Debug)]
+enum Escape {
+    None,
+    Start,
+    Csi(String),
+}
+
+/// A character grid that can be rendered to as if it was the terminal.
+///
+/// The text written to a `Canvas` is placed at the cursor, which is moved by the cursor escape
+/// sequences (`Goto`). The other escape sequences (e.g. colors) are ignored, except for the clear
+/// of the whole screen, and the text outside of the canvas is discarded.
+///
+/// # Example
+///
+/// ```
+/// use std::io::Write;
+/// use termion::{color, cursor};
+/// use crabbit::graphics::{canvas::Canvas, Size};
+///
+/// let mut canvas = Canvas::new(Size { width: 5, height: 2 });
+/// write!(canvas, "{}{}ab{}", cursor::Goto(2, 2), color::Fg(color::Red), color::Fg(color::Reset))
+///     .unwrap();
+/// assert_eq!(canvas.to_string(), "     \n ab  \n");
+/// ```
+#[derive(Debug)]
+pub struct Canvas {
+    size: Size,
+    cells: Vec<char>,
+    cursor: Pos,
+    escape: Escape,
+    pending: Vec<u8>,
+}
+
+impl Canvas {
+    /// Returns a new blank `Canvas` of the given `size`.
+    pub fn new(size: Size) -> Self {
+        let cells = vec![' '; size.width as usize * size.height as usize];
+        Canvas {
+            size,
+            cells,
+            cursor: Pos { col: 1, row: 1 },
+            escape: Escape::None,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Returns the size of the canvas.
+    pub fn size(&self) -> &Size {
+        &self.size
+    }
+
+    /// Returns the character at `pos` (starting at `(1, 1)`, as the terminal), if any.
+    pub fn get(&self, pos: Pos) -> Option<char> {
+        self.index(pos).map(|i| self.cells[i])
+    }
+
+    /// Returns an iterator over the rows of the canvas.
+    pub fn rows(&self) -> Chunks<'_, char> {
+        self.cells.chunks(self.size.width.max(1) as usize)
+    }
+
+    /// Blanks the whole canvas.
+    pub fn clear(&mut self) {
+        self.cells.fill(' ');
+    }
+
+    /// Returns the index of the cell at `pos`, if it is inside the canvas.
+    fn index(&self, pos: Pos) -> Option<usize> {
+        let (col, row) = (pos.col.checked_sub(1)?, pos.row.checked_sub(1)?);
+        if col < self.size.width && row < self.size.height {
+            Some(row as usize * self.size.width as usize + col as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Puts `ch` at the cursor and moves the cursor to the next column.
+    fn put(&mut self, ch: char) {
+        match ch {
+            '\n' => {
+                self.cursor = Pos {
+                    col: 1,
+                    row: self.cursor.row.saturating_add(1),
+                }
+            }
+            '\r' => self.cursor.col = 1,
+            _ => {
+                if let Some(i) = self.index(self.cursor) {
+                    self.cells[i] = ch;
+                }
+                self.cursor.col = self.cursor.col.saturating_add(1);
+            }
+        }
+    }
+
+    /// Runs the control sequence with the given parameters and final byte.
+    fn control(&mut self, params: &str, action: u8) {
+        let mut args = params.split(';').map(|arg| arg.parse::<u16>().unwrap_or(1));
+        match action {
+            b'H' | b'f' => {
+                let row = args.next().unwrap_or(1);
+                let col = args.next().unwrap_or(1);
+                self.cursor = Pos { col, row };
+            }
+            b'J' if params == "2" => self.clear(),
+            _ => (),
+        }
+    }
+
+    /// Parses a byte of the written text.
+    fn feed(&mut self, byte: u8) {
+        match &mut self.escape {
+            Escape::None if byte == 0x1b => self.escape = Escape::Start,
+            Escape::None => {
+                self.pending.push(byte);
+                match std::str::from_utf8(&self.pending) {
+                    Ok(text) => {
+                        let ch = text.chars().next().unwrap_or(' ');
+                        self.pending.clear();
+                        self.put(ch);
+                    }
+                    // the character is incomplete
+                    Err(e) if e.error_len().is_none() => (),
+                    Err(_) => {
+                        self.pending.clear();
+                        self.put(char::REPLACEMENT_CHARACTER);
+                    }
+                }
+            }
+            Escape::Start if byte == b'[' => self.escape = Escape::Csi(String::new()),
+            Escape::Start => self.escape = Escape::None,
+            Escape::Csi(params) if (0x20..0x40).contains(&byte) => params.push(byte as char),
+            Escape::Csi(params) => {
+                let params = std::mem::take(params);
+                self.escape = Escape::None;
+                self.control(&params, byte);
+            }
+        }
+    }
+}
+
+impl io::Write for Canvas {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        buf.iter().for_each(|byte| self.feed(*byte));
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl fmt::Display for Canvas {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            writeln!(f, "{}", row.iter().collect::<String>())?;
+        }
+        Ok(())
+    }
+}
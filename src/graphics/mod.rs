@@ -1,10 +1,11 @@
-use std::io::Stdout;
-use termion::raw::RawTerminal;
+use std::io::Write;
 
+pub mod canvas;
+pub mod mono;
 pub mod object;
 
-/// Raw terminal output.
-pub type TermOut = RawTerminal<Stdout>;
+/// Terminal output: the raw terminal or any other writer (e.g. to render the game off-screen).
+pub type TermOut = dyn Write;
 
 /// Defines behavior of objects that can be rendered.
 pub trait Render {
@@ -15,7 +16,7 @@ pub trait Render {
 }
 
 /// Terminal position (row, col).
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Pos {
     /// Column
     pub col: u16,
@@ -0,0 +1,126 @@
+//! A terminal output without colors.
+
+use std::io::{self, Write};
+
+/// State of the escape sequence being filtered.
+#[derive(Debug)]
+enum Escape {
+    None,
+    Start,
+    Csi(Vec<u8>),
+}
+
+/// A writer that drops the colors from the graphic rendition escape sequences of the text written
+/// to it before passing it to the inner writer. The other attributes (such as bold or inverted
+/// text, which mark the selected entry of the menus) are kept.
+///
+/// # Example
+///
+/// ```
+/// use std::io::Write;
+/// use termion::{color, cursor, style};
+/// use crabbit::graphics::mono::Monochrome;
+///
+/// let mut out = Monochrome::new(Vec::new());
+/// write!(out, "{}{}ab{}", cursor::Goto(2, 2), color::Fg(color::Red), color::Fg(color::Reset))
+///     .unwrap();
+/// write!(out, "{}{}c{}", color::Bg(color::Blue), style::Invert, style::Reset).unwrap();
+/// let text = format!("{}ab{}c{}", cursor::Goto(2, 2), style::Invert, style::Reset);
+/// assert_eq!(out.into_inner(), text.into_bytes());
+/// ```
+#[derive(Debug)]
+pub struct Monochrome<W: Write> {
+    inner: W,
+    escape: Escape,
+}
+
+impl<W: Write> Monochrome<W> {
+    /// Returns a new `Monochrome` writing to `inner`.
+    pub fn new(inner: W) -> Self {
+        Monochrome {
+            inner,
+            escape: Escape::None,
+        }
+    }
+
+    /// Returns the inner writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for Monochrome<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut text = Vec::with_capacity(buf.len());
+        for &byte in buf {
+            self.escape = match std::mem::replace(&mut self.escape, Escape::None) {
+                Escape::None if byte == 0x1b => Escape::Start,
+                Escape::None => {
+                    text.push(byte);
+                    Escape::None
+                }
+                Escape::Start if byte == b'[' => Escape::Csi(vec![0x1b, byte]),
+                Escape::Start => {
+                    text.extend([0x1b, byte]);
+                    Escape::None
+                }
+                Escape::Csi(mut seq) => {
+                    seq.push(byte);
+                    // a byte in `@..=~` ends the sequence, whose colors are dropped
+                    if !(0x40..=0x7e).contains(&byte) {
+                        Escape::Csi(seq)
+                    } else {
+                        if byte != b'm' {
+                            text.extend(seq);
+                        } else if let Some(params) = without_colors(&seq[2..seq.len() - 1]) {
+                            text.extend(b"\x1b[");
+                            text.extend(params);
+                            text.push(b'm');
+                        }
+                        Escape::None
+                    }
+                }
+            };
+        }
+        self.inner.write_all(&text)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Returns the parameters of a graphic rendition sequence without the ones that set colors, or
+/// `None` if only colors are left out.
+fn without_colors(params: &[u8]) -> Option<Vec<u8>> {
+    if params.is_empty() {
+        // a reset
+        return Some(Vec::new());
+    }
+
+    let mut kept: Vec<&[u8]> = Vec::new();
+    let mut params = params.split(|&b| b == b';' || b == b':');
+    while let Some(param) = params.next() {
+        let code: u16 = std::str::from_utf8(param)
+            .ok()
+            .and_then(|p| p.parse().ok())
+            .unwrap_or(0);
+        match code {
+            // an extended color: `5;<index>` or `2;<r>;<g>;<b>`
+            38 | 48 | 58 => {
+                let skip = match params.next() {
+                    Some(b"5") => 1,
+                    Some(b"2") => 3,
+                    _ => 0,
+                };
+                for _ in 0..skip {
+                    params.next();
+                }
+            }
+            30..=39 | 40..=49 | 59 | 90..=97 | 100..=107 => (),
+            _ => kept.push(param),
+        }
+    }
+    (!kept.is_empty()).then(|| kept.join(&b';'))
+}
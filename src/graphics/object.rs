@@ -3,7 +3,8 @@
 
 use std::{
     fs::File,
-    io::{prelude::*, BufReader},
+    io::{self, prelude::*, BufReader},
+    path::Path,
     slice::Chunks,
 };
 use termion::{
@@ -14,7 +15,7 @@ use termion::{
 use super::{Pos, Render, Size, TermOut};
 
 /// Graphical representation of an [`Obj`]. An ANSII Matrix.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Sprite {
     size: Size,
     chars: Vec<char>,
@@ -25,7 +26,7 @@ impl Sprite {
     pub fn new(chars: Vec<char>, width: u16) -> Sprite {
         let n = chars.len() as u16;
 
-        if width > n || n % width != 0 {
+        if width > n || !n.is_multiple_of(width) {
             panic!("Invalid width for a char matrix with {} values.", n);
         }
 
@@ -35,28 +36,84 @@ impl Sprite {
     }
 
     /// Return a sprite extracting its charaters from a text file.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the file can't be read (see [`Sprite::load`]).
     pub fn from_file(fpath: &str) -> Sprite {
-        let file = File::open(fpath).expect("Failed to read the sprite file!");
-        let reader = BufReader::new(file);
-        let mut ascii_matrix = Vec::new();
-        let mut width = 0;
-
-        let lines: Vec<_> = reader
-            .lines()
-            .map(|line| {
-                let line = line.expect("Failed to read file line!");
-                width = width.max(line.len());
-                line
-            })
-            .collect();
-
-        lines.into_iter().for_each(|mut line| {
-            let padding = " ".repeat(width - line.len());
-            line.push_str(&padding);
+        Self::load(fpath).expect("Failed to read the sprite file!")
+    }
+
+    /// Return a sprite extracting its charaters from a text file, padding the lines to the
+    /// width of the longest one.
+    ///
+    /// Fails if the file can't be read or has no characters.
+    pub fn load<P: AsRef<Path>>(fpath: P) -> io::Result<Sprite> {
+        let reader = BufReader::new(File::open(fpath)?);
+        let lines = reader.lines().collect::<io::Result<Vec<_>>>()?;
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        if width == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the sprite is empty",
+            ));
+        }
+
+        let mut ascii_matrix = Vec::with_capacity(width * lines.len());
+        for line in lines {
+            let len = ascii_matrix.len();
             ascii_matrix.extend(line.chars());
-        });
+            ascii_matrix.resize(len + width, ' ');
+        }
+        Ok(Self::new(ascii_matrix, width as u16))
+    }
+
+    /// Checks the text of a sprite file for what doesn't display as expected. Returns a message
+    /// for each problem found.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use crabbit::graphics::object::Sprite;
+    ///
+    /// assert!(Sprite::lint(" /\\\n/  \\\n").is_empty());
+    /// assert_eq!(Sprite::lint("\to\n\n"), [
+    ///     "line 1, column 1: tab (it has no fixed width)",
+    ///     "line 2: blank line at the end (it makes the sprite taller)",
+    /// ]);
+    /// ```
+    pub fn lint(text: &str) -> Vec<String> {
+        let mut problems = Vec::new();
+        if text.trim().is_empty() {
+            problems.push("the sprite is blank".to_string());
+            return problems;
+        }
 
-        Self::new(ascii_matrix, width as u16)
+        let lines: Vec<_> = text.lines().collect();
+        for (i, line) in lines.iter().enumerate() {
+            for (j, c) in line.chars().enumerate() {
+                let what = match c {
+                    '\t' => "tab (it has no fixed width)",
+                    c if c.is_control() => "control character",
+                    _ => continue,
+                };
+                problems.push(format!("line {}, column {}: {}", i + 1, j + 1, what));
+            }
+        }
+
+        let blank = lines.iter().rev().take_while(|l| l.trim().is_empty());
+        let first_blank = lines.len() - blank.count();
+        for i in first_blank..lines.len() {
+            problems.push(format!(
+                "line {}: blank line at the end (it makes the sprite taller)",
+                i + 1
+            ));
+        }
+        problems
     }
 
     /// Returns the sprite size as a tuple (width, height).
@@ -95,7 +152,7 @@ impl Sprite {
     }
 
     /// Returns a iterator over the sprite rows.
-    pub fn rows(&self) -> Chunks<char> {
+    pub fn rows(&self) -> Chunks<'_, char> {
         self.chars.chunks(self.size.width as usize)
     }
 }
@@ -124,8 +181,7 @@ impl Obj {
 
 impl Render for Obj {
     fn render(&self, out: &mut TermOut) {
-        let mut row = self.pos.row;
-        for line in self.sprite.rows() {
+        for (row, line) in (self.pos.row..).zip(self.sprite.rows()) {
             let line: String = line.iter().collect();
             write!(
                 out,
@@ -137,7 +193,6 @@ impl Render for Obj {
                 Bg(Reset)
             )
             .unwrap();
-            row += 1;
         }
     }
 
@@ -0,0 +1,265 @@
+//! Key bindings.
+
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt};
+use termion::event::Key;
+
+use super::Act;
+
+/// Key bindings as written in the settings file: action names mapped to key names.
+type Names = BTreeMap<String, Vec<String>>;
+
+/// Maps keys to user actions.
+///
+/// An action can be triggered by many keys, but a key triggers at most one action. Letters are
+/// case insensitive.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "Names", into = "Names")]
+pub struct KeyMap {
+    // one entry for each action, in the same order as `Act::ALL`.
+    binds: Vec<(Act, Vec<Key>)>,
+}
+
+impl KeyMap {
+    /// Returns a new `KeyMap` without any binding.
+    pub fn empty() -> Self {
+        let binds = Act::ALL.into_iter().map(|act| (act, Vec::new())).collect();
+        KeyMap { binds }
+    }
+
+    /// Returns the action triggered by `key`, if any.
+    pub fn act(&self, key: Key) -> Option<Act> {
+        let key = normalize(key);
+        self.binds
+            .iter()
+            .find(|(_, keys)| keys.contains(&key))
+            .map(|(act, _)| *act)
+    }
+
+    /// Returns the keys that trigger `act`.
+    pub fn keys(&self, act: Act) -> &[Key] {
+        &self.entry(act).1
+    }
+
+    /// Makes `key` trigger `act`.
+    ///
+    /// Fails if `key` already triggers another action.
+    pub fn bind(&mut self, act: Act, key: Key) -> Result<(), KeyMapError> {
+        let key = normalize(key);
+        match self.act(key) {
+            Some(bound) if bound == act => Ok(()),
+            Some(bound) => Err(KeyMapError::Conflict(key, bound, act)),
+            None => {
+                self.entry_mut(act).1.push(key);
+                Ok(())
+            }
+        }
+    }
+
+    /// Removes the last key bound to `act` and returns it, unless it is the only key that
+    /// triggers `act` (so the action can't be locked out).
+    pub fn unbind_last(&mut self, act: Act) -> Option<Key> {
+        let keys = &mut self.entry_mut(act).1;
+        if keys.len() > 1 {
+            keys.pop()
+        } else {
+            None
+        }
+    }
+
+    fn entry(&self, act: Act) -> &(Act, Vec<Key>) {
+        self.binds
+            .iter()
+            .find(|(a, _)| *a == act)
+            .expect("Every action has an entry.")
+    }
+
+    fn entry_mut(&mut self, act: Act) -> &mut (Act, Vec<Key>) {
+        self.binds
+            .iter_mut()
+            .find(|(a, _)| *a == act)
+            .expect("Every action has an entry.")
+    }
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        let defaults = [
+            (Act::PlayerJump, "space up enter w k"),
+            (Act::Pause, "esc p"),
+            (Act::Restart, "r"),
+            (Act::Quit, "q"),
+            (Act::Settings, "s"),
+        ];
+
+        let mut map = KeyMap::empty();
+        for (act, keys) in defaults {
+            for key in keys.split(' ') {
+                let key = parse(key).expect("Invalid default key.");
+                map.bind(act, key).expect("Conflicting default keys.");
+            }
+        }
+        map
+    }
+}
+
+impl TryFrom<Names> for KeyMap {
+    type Error = KeyMapError;
+
+    /// Actions missing in `names` (or without keys, so they can't be locked out) keep their
+    /// default keys.
+    fn try_from(names: Names) -> Result<Self, Self::Error> {
+        let mut custom = Vec::with_capacity(names.len());
+        for (act, keys) in names {
+            let act = Act::from_name(&act).ok_or(KeyMapError::UnknownAct(act))?;
+            let keys = keys
+                .into_iter()
+                .map(|key| parse(&key).ok_or(KeyMapError::UnknownKey(key)))
+                .collect::<Result<Vec<_>, _>>()?;
+            custom.push((act, keys));
+        }
+
+        let defaults = KeyMap::default();
+        let mut map = KeyMap::empty();
+        for act in Act::ALL {
+            let keys = match custom.iter().find(|(a, _)| *a == act) {
+                Some((_, keys)) if !keys.is_empty() => keys.as_slice(),
+                _ => defaults.keys(act),
+            };
+            for key in keys {
+                map.bind(act, *key)?;
+            }
+        }
+        Ok(map)
+    }
+}
+
+impl From<KeyMap> for Names {
+    fn from(map: KeyMap) -> Self {
+        map.binds
+            .into_iter()
+            .map(|(act, keys)| (act.name().to_string(), keys.into_iter().map(name).collect()))
+            .collect()
+    }
+}
+
+/// Errors on key bindings.
+#[derive(Debug)]
+pub enum KeyMapError {
+    /// There is no action with the given name.
+    UnknownAct(String),
+    /// There is no key with the given name.
+    UnknownKey(String),
+    /// The key is bound to two different actions.
+    Conflict(Key, Act, Act),
+}
+
+impl fmt::Display for KeyMapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownAct(act) => write!(f, "unknown action \"{}\"", act),
+            Self::UnknownKey(key) => write!(f, "unknown key \"{}\"", key),
+            Self::Conflict(key, a, b) => write!(
+                f,
+                "key \"{}\" is bound to both \"{}\" and \"{}\"",
+                name(*key),
+                a.name(),
+                b.name()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for KeyMapError {}
+
+/// Lowercases letters, so bindings are case insensitive.
+fn normalize(key: Key) -> Key {
+    match key {
+        Key::Char(c) => Key::Char(c.to_ascii_lowercase()),
+        key => key,
+    }
+}
+
+/// Returns the `Key` with the given name (as written in the settings file).
+///
+/// Besides single characters, the names `space`, `enter`, `tab`, `shift-tab`, `esc`,
+/// `backspace`, `delete`, `insert`, `home`, `end`, `pageup`, `pagedown`, `up`, `down`, `left`,
+/// `right`, `null` and `f<n>` are accepted, as well as `ctrl-<char>` and `alt-<char>`. Every key
+/// read from the terminal has a name (see [`name`]).
+pub fn parse(name: &str) -> Option<Key> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(normalize(Key::Char(c)));
+    }
+
+    let key = match name.to_ascii_lowercase().as_str() {
+        "space" => Key::Char(' '),
+        "enter" => Key::Char('\n'),
+        "tab" => Key::Char('\t'),
+        "shift-tab" => Key::BackTab,
+        "esc" => Key::Esc,
+        "backspace" => Key::Backspace,
+        "delete" => Key::Delete,
+        "insert" => Key::Insert,
+        "home" => Key::Home,
+        "end" => Key::End,
+        "pageup" => Key::PageUp,
+        "pagedown" => Key::PageDown,
+        "up" => Key::Up,
+        "down" => Key::Down,
+        "left" => Key::Left,
+        "right" => Key::Right,
+        "null" => Key::Null,
+        lower => {
+            // the prefixes are ASCII, so they have the same length in `name`
+            if let Some(c) = lower.strip_prefix("ctrl-") {
+                Key::Ctrl(single_char(&name[name.len() - c.len()..])?)
+            } else if let Some(c) = lower.strip_prefix("alt-") {
+                Key::Alt(single_char(&name[name.len() - c.len()..])?)
+            } else if let Some(n) = lower.strip_prefix('f') {
+                Key::F(n.parse().ok()?)
+            } else {
+                return None;
+            }
+        }
+    };
+    Some(key)
+}
+
+/// Returns the name of `key` (as written in the settings file), which [`parse`] reads back.
+pub fn name(key: Key) -> String {
+    let name = match key {
+        Key::Char(' ') => "space",
+        Key::Char('\n') => "enter",
+        Key::Char('\t') => "tab",
+        Key::Char(c) => return c.to_string(),
+        Key::BackTab => "shift-tab",
+        Key::Esc => "esc",
+        Key::Backspace => "backspace",
+        Key::Delete => "delete",
+        Key::Insert => "insert",
+        Key::Home => "home",
+        Key::End => "end",
+        Key::PageUp => "pageup",
+        Key::PageDown => "pagedown",
+        Key::Up => "up",
+        Key::Down => "down",
+        Key::Left => "left",
+        Key::Right => "right",
+        Key::Null => "null",
+        Key::F(n) => return format!("f{}", n),
+        Key::Ctrl(c) => return format!("ctrl-{}", c),
+        Key::Alt(c) => return format!("alt-{}", c),
+        // not a key (see `termion::event::Key`)
+        _ => "null",
+    };
+    name.to_string()
+}
+
+fn single_char(s: &str) -> Option<char> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
@@ -0,0 +1,114 @@
+//! Support for the [kitty keyboard protocol], which makes the terminal report key releases and
+//! repeats besides key presses.
+//!
+//! Terminals that don't support the protocol ignore [`KITTY_ON`] and keep reporting only key
+//! presses, in which case the phases are guessed by the [`InputQueue`].
+//!
+//! [kitty keyboard protocol]: https://sw.kovidgoyal.net/kitty/keyboard-protocol/
+//! [`InputQueue`]: super::InputQueue
+
+use termion::event::Key;
+
+/// Turns on the protocol (disambiguate keys, report event types and report all keys as escape
+/// codes).
+pub const KITTY_ON: &str = "\x1b[>11u";
+/// Turns off the protocol (restores the previous keyboard mode).
+pub const KITTY_OFF: &str = "\x1b[<u";
+
+/// Phase of a key press.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Phase {
+    /// The key was pressed.
+    Press,
+    /// The key is still down (the terminal is repeating it).
+    Hold,
+    /// The key was released.
+    Release,
+}
+
+// modifier bits (the reported value is 1 + the bits)
+const ALT: u32 = 0b10;
+const CTRL: u32 = 0b100;
+
+/// Parses a CSI sequence (`ESC [ <params> <fin>`) reported by the protocol.
+///
+/// Returns `None` if the sequence doesn't use the protocol (it is a legacy sequence or some other
+/// kind of event), in which case it should be parsed as usual.
+pub fn parse_csi(params: &[u8], fin: u8) -> Option<(Key, Phase)> {
+    let params = std::str::from_utf8(params).ok()?;
+    // legacy sequences never have an event type (separated by ':')
+    if fin != b'u' && !params.contains(':') {
+        return None;
+    }
+
+    let mut fields = params.split(';');
+    let code: u32 = match fields.next()?.split(':').next()? {
+        "" => 1,
+        code => code.parse().ok()?,
+    };
+    let mut mods_field = fields.next().unwrap_or("1").split(':');
+    let mods = mods_field.next()?.parse::<u32>().ok()?.checked_sub(1)?;
+    let phase = match mods_field.next().unwrap_or("1") {
+        "1" => Phase::Press,
+        "2" => Phase::Hold,
+        "3" => Phase::Release,
+        _ => return None,
+    };
+
+    let key = match fin {
+        b'u' => text_key(code, mods)?,
+        b'A' => Key::Up,
+        b'B' => Key::Down,
+        b'C' => Key::Right,
+        b'D' => Key::Left,
+        b'H' => Key::Home,
+        b'F' => Key::End,
+        b'P' => Key::F(1),
+        b'Q' => Key::F(2),
+        b'S' => Key::F(4),
+        b'~' => tilde_key(code)?,
+        _ => return None,
+    };
+    Some((key, phase))
+}
+
+/// Returns the key of a `CSI <code> u` sequence.
+fn text_key(code: u32, mods: u32) -> Option<Key> {
+    let key = match code {
+        27 => Key::Esc,
+        13 => Key::Char('\n'),
+        9 => Key::Char('\t'),
+        127 => Key::Backspace,
+        // private use area: keypad, media and modifier keys
+        57344..=63743 => return None,
+        code => {
+            let c = char::from_u32(code)?;
+            if mods & CTRL != 0 {
+                Key::Ctrl(c)
+            } else if mods & ALT != 0 {
+                Key::Alt(c)
+            } else {
+                Key::Char(c)
+            }
+        }
+    };
+    Some(key)
+}
+
+/// Returns the key of a `CSI <code> ~` sequence.
+fn tilde_key(code: u32) -> Option<Key> {
+    let key = match code {
+        2 => Key::Insert,
+        3 => Key::Delete,
+        5 => Key::PageUp,
+        6 => Key::PageDown,
+        7 => Key::Home,
+        8 => Key::End,
+        13 => Key::F(3),
+        15 => Key::F(5),
+        17..=21 => Key::F(code as u8 - 11),
+        23 | 24 => Key::F(code as u8 - 12),
+        _ => return None,
+    };
+    Some(key)
+}
@@ -0,0 +1,55 @@
+//! User input: the actions that the user can take and how they are triggered.
+
+pub mod keys;
+pub mod kitty;
+pub mod queue;
+pub use keys::KeyMap;
+pub use kitty::Phase;
+pub use queue::{InputEvent, InputQueue};
+
+use crate::graphics::Pos;
+
+/// Turns on the mouse reporting of the terminal (button presses in the SGR format).
+pub const MOUSE_ON: &str = "\x1b[?1000h\x1b[?1006h";
+/// Turns off the mouse reporting of the terminal.
+pub const MOUSE_OFF: &str = "\x1b[?1006l\x1b[?1000l";
+
+/// Posible user actions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Act {
+    PlayerJump,
+    Pause,
+    Restart,
+    Quit,
+    Settings,
+    /// A mouse click on the given position.
+    Click(Pos),
+}
+
+impl Act {
+    /// All the actions that can be bound to keys, in the order that they are displayed.
+    pub const ALL: [Act; 5] = [
+        Act::PlayerJump,
+        Act::Pause,
+        Act::Restart,
+        Act::Quit,
+        Act::Settings,
+    ];
+
+    /// Returns the name of the action (as written in the settings file).
+    pub fn name(&self) -> &'static str {
+        match self {
+            Act::PlayerJump => "jump",
+            Act::Pause => "pause",
+            Act::Restart => "restart",
+            Act::Quit => "quit",
+            Act::Settings => "settings",
+            Act::Click(_) => "click",
+        }
+    }
+
+    /// Returns the action with the given name (see [`Act::name`]).
+    pub fn from_name(name: &str) -> Option<Act> {
+        Self::ALL.into_iter().find(|act| act.name() == name)
+    }
+}
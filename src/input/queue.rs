@@ -0,0 +1,280 @@
+//! Queue of the user input (keys and mouse clicks), read from `stdin` on a background thread.
+
+use std::{
+    collections::VecDeque,
+    io::{self, ErrorKind},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+use termion::event::{self, Event, Key, MouseButton, MouseEvent};
+
+use super::kitty::{self, Phase};
+
+/// A key pressed or a mouse click made by the user.
+#[derive(Debug, Clone)]
+pub struct InputEvent {
+    /// A key press or a mouse button press (other events aren't queued).
+    pub event: Event,
+    /// Phase of the key press (mouse clicks are always pressed).
+    pub phase: Phase,
+}
+
+/// An event read from `stdin`, whose phase is only known if the terminal reported it.
+struct RawEvent {
+    event: Event,
+    phase: Option<Phase>,
+    time: Instant,
+}
+
+/// A key that has been pressed recently.
+#[derive(Debug)]
+struct Held {
+    key: Key,
+    last: Instant,
+    repeating: bool,
+}
+
+/// Queues the keys pressed and the mouse clicks made by the user.
+///
+/// The input is read on a background thread that never blocks on the queue, so no key press is
+/// lost. The thread stops when the queue is dropped (or [`InputQueue::shutdown`] is called).
+///
+/// Mouse clicks are only reported by the terminal after writing [`MOUSE_ON`] to it. Likewise, key
+/// releases and repeats are only reported after writing [`KITTY_ON`]. If the terminal doesn't
+/// report them, a key pressed again right after the previous press is considered held (repeated
+/// by the terminal) and released when the repetitions stop.
+///
+/// [`MOUSE_ON`]: super::MOUSE_ON
+/// [`KITTY_ON`]: super::kitty::KITTY_ON
+#[derive(Debug)]
+pub struct InputQueue {
+    rx: Receiver<RawEvent>,
+    pending: VecDeque<InputEvent>,
+    held: Vec<Held>,
+    reports_releases: bool,
+    stop: Arc<AtomicBool>,
+    reader: Option<JoinHandle<()>>,
+}
+
+impl InputQueue {
+    // how long the reader waits for input before checking if it should stop (milliseconds)
+    const POLL_TIMEOUT: i32 = 50;
+    // maximum time between two presses of a key repeated by the terminal
+    const REPEAT_GAP: Duration = Duration::from_millis(100);
+    // time without repetitions after which a held key is considered released
+    const RELEASE_GAP: Duration = Duration::from_millis(150);
+
+    /// Starts reading the keys pressed on `stdin`.
+    pub fn spawn() -> Self {
+        let (tx, rx) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let reader_stop = Arc::clone(&stop);
+        let reader = thread::spawn(move || Self::read(tx, reader_stop));
+
+        InputQueue {
+            rx,
+            pending: VecDeque::new(),
+            held: Vec::new(),
+            reports_releases: false,
+            stop,
+            reader: Some(reader),
+        }
+    }
+
+    /// Returns the oldest pending event, if any.
+    ///
+    /// Consecutive repetitions of a key held down that are pending at the same time are coalesced
+    /// into a single event. Presses are never coalesced, so two quick presses of a key are two
+    /// events.
+    pub fn pop(&mut self) -> Option<InputEvent> {
+        self.fetch();
+        self.pending.pop_front()
+    }
+
+    /// Returns `true` if the terminal reports key releases (see [`KITTY_ON`]), so a key pressed is
+    /// known to be down until its release is reported.
+    ///
+    /// [`KITTY_ON`]: super::kitty::KITTY_ON
+    pub fn reports_releases(&self) -> bool {
+        self.reports_releases
+    }
+
+    /// Stops reading the input and waits for the reader thread to finish.
+    pub fn shutdown(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(reader) = self.reader.take() {
+            reader.join().expect("Input reader thread panicked.");
+        }
+    }
+
+    /// Moves the events read by the reader thread to the pending queue.
+    fn fetch(&mut self) {
+        while let Ok(raw) = self.rx.try_recv() {
+            self.push(raw);
+        }
+        self.release_held();
+    }
+
+    /// Adds an event to the pending queue.
+    fn push(&mut self, raw: RawEvent) {
+        let phase = match raw.phase {
+            Some(phase) => {
+                self.reports_releases = true;
+                phase
+            }
+            None if self.reports_releases => Phase::Press,
+            None => self.guess_phase(&raw),
+        };
+
+        let repeated = self.pending.back().is_some_and(|last| {
+            phase == Phase::Hold && last.phase == phase && last.event == raw.event
+        });
+        if !repeated {
+            self.pending.push_back(InputEvent {
+                event: raw.event,
+                phase,
+            });
+        }
+    }
+
+    /// Guesses the phase of an event when the terminal doesn't report it.
+    fn guess_phase(&mut self, raw: &RawEvent) -> Phase {
+        let key = match raw.event {
+            Event::Key(key) => key,
+            _ => return Phase::Press,
+        };
+
+        match self.held.iter_mut().find(|held| held.key == key) {
+            Some(held) => {
+                held.repeating = raw.time.duration_since(held.last) <= Self::REPEAT_GAP;
+                held.last = raw.time;
+                if held.repeating {
+                    Phase::Hold
+                } else {
+                    Phase::Press
+                }
+            }
+            None => {
+                self.held.push(Held {
+                    key,
+                    last: raw.time,
+                    repeating: false,
+                });
+                Phase::Press
+            }
+        }
+    }
+
+    /// Forgets the keys that aren't repeated anymore, queuing the release of the held ones.
+    fn release_held(&mut self) {
+        let now = Instant::now();
+        let pending = &mut self.pending;
+        self.held.retain(|held| {
+            let released = now.duration_since(held.last) > Self::RELEASE_GAP;
+            if released && held.repeating {
+                pending.push_back(InputEvent {
+                    event: Event::Key(held.key),
+                    phase: Phase::Release,
+                });
+            }
+            !released
+        });
+    }
+
+    /// Reads `stdin` until `stop` is set or the input is closed.
+    fn read(tx: Sender<RawEvent>, stop: Arc<AtomicBool>) {
+        let mut buf = [0; 64];
+        let mut fds = libc::pollfd {
+            fd: libc::STDIN_FILENO,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+
+        while !stop.load(Ordering::Relaxed) {
+            // SAFETY: `fds` is a single valid pollfd.
+            let ready = unsafe { libc::poll(&mut fds, 1, Self::POLL_TIMEOUT) };
+            match ready {
+                0 => continue,
+                r if r < 0 && io::Error::last_os_error().kind() == ErrorKind::Interrupted => {
+                    continue
+                }
+                r if r < 0 => break,
+                _ => (),
+            }
+
+            // SAFETY: `buf` is valid for writes of `buf.len()` bytes.
+            let n = unsafe { libc::read(libc::STDIN_FILENO, buf.as_mut_ptr().cast(), buf.len()) };
+            if n <= 0 {
+                break;
+            }
+
+            let time = Instant::now();
+            for (event, phase) in Self::parse(&buf[..n as usize]) {
+                if tx.send(RawEvent { event, phase, time }).is_err() {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Parses the queued events in `bytes`.
+    fn parse(bytes: &[u8]) -> Vec<(Event, Option<Phase>)> {
+        let mut events = Vec::new();
+        let mut i = 0;
+
+        while i < bytes.len() {
+            let b = bytes[i];
+            i += 1;
+
+            // an escape character alone is the escape key, not the start of a sequence.
+            if b == 0x1B && i == bytes.len() {
+                events.push((Event::Key(Key::Esc), None));
+                continue;
+            }
+
+            // CSI sequences (parameters followed by a final byte) may use the kitty protocol.
+            if b == 0x1B && bytes[i] == b'[' {
+                let seq = &bytes[i + 1..];
+                if let Some(len) = seq.iter().position(|b| (0x40..=0x7E).contains(b)) {
+                    if let Some((key, phase)) = kitty::parse_csi(&seq[..len], seq[len]) {
+                        events.push((Event::Key(key), Some(phase)));
+                        i += len + 2;
+                        continue;
+                    }
+                }
+            }
+
+            let mut rest = bytes[i..].iter().map(|b| Ok(*b));
+            let event = event::parse_event(b, &mut rest);
+            i = bytes.len() - rest.len();
+            match event {
+                Ok(event) if Self::is_queued(&event) => events.push((event, None)),
+                _ => (),
+            }
+        }
+        events
+    }
+
+    /// Returns `true` for key presses and mouse button (not wheel) presses.
+    fn is_queued(event: &Event) -> bool {
+        match event {
+            Event::Key(_) => true,
+            Event::Mouse(MouseEvent::Press(button, _, _)) => {
+                !matches!(button, MouseButton::WheelUp | MouseButton::WheelDown)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Drop for InputQueue {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
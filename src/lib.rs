@@ -1,7 +1,12 @@
 /// All the `Game` components (elements).
 pub mod components;
+pub mod config;
+pub mod dirs;
 pub mod game;
 #[warn(missing_debug_implementations, missing_docs)]
-
 /// Graphical utils.
 pub mod graphics;
+pub mod input;
+pub mod records;
+pub mod settings;
+pub mod stats;
@@ -1,67 +1,345 @@
-use std::io;
-use termion::{color, raw::IntoRawMode};
+use clap::{
+    builder::{PossibleValuesParser, TypedValueParser},
+    Args, Parser, Subcommand,
+};
+use std::{
+    env, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process,
+};
+use termion::{
+    color::{AnsiValue, Fg, Reset},
+    raw::IntoRawMode,
+};
 
 use crabbit::{
-    components::{
-        enemies::{Enemies, Walls},
-        hud::{Hud, Splash},
-        player::Player,
-        stage::{Layer, Stage},
-    },
-    game::Game,
-    graphics::{object::Sprite, Pos},
+    config::{ColorCode, Config},
+    game::{replay::Replay, Difficulty, Mode, Runner},
+    graphics::{mono::Monochrome, object::Sprite},
+    records::{Records, RecordsError},
+    settings::Settings,
+    stats::{Stats, StatsError},
 };
 
+/// A simple terminal jumper game.
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    /// What to do (plays the game by default).
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Plays the game.
+    Play(PlayArgs),
+    /// Plays back a recorded session.
+    Replay(ReplayArgs),
+    /// Shows the statistics and the records of the played runs.
+    Stats,
+    /// Previews and checks sprite files.
+    #[command(subcommand)]
+    Sprite(SpriteCommand),
+    /// Opens the settings file in the text editor ($VISUAL or $EDITOR).
+    Edit,
+}
+
+/// Options of how the game is displayed.
+#[derive(Args, Default)]
+struct ScreenArgs {
+    /// Game config file (stage, player, enemies and HUD) [default: the built-in one].
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+    /// Displays the game without colors.
+    #[arg(long)]
+    no_color: bool,
+}
+
+#[derive(Args, Default)]
+struct PlayArgs {
+    /// Seed of the first run.
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Difficulty of the game [default: the one in the settings].
+    #[arg(
+        long,
+        value_parser = PossibleValuesParser::new(Difficulty::ALL.map(|d| d.name()))
+            .map(|name| Difficulty::from_name(&name).expect("Possible difficulty.")),
+    )]
+    difficulty: Option<Difficulty>,
+    /// Game mode selected in the main menu.
+    #[arg(
+        long,
+        default_value = "endless",
+        value_parser = PossibleValuesParser::new(Mode::ALL.map(|m| m.name()))
+            .map(|name| Mode::from_name(&name).expect("Possible mode.")),
+    )]
+    mode: Mode,
+    /// Records the session to a file.
+    #[arg(long, value_name = "FILE")]
+    record: Option<PathBuf>,
+    /// Makes the autopilot play instead of the user.
+    #[arg(long)]
+    autoplay: bool,
+    /// Reloads the config and the sprites when their files change, without restarting the run.
+    #[arg(long)]
+    dev: bool,
+    #[command(flatten)]
+    screen: ScreenArgs,
+}
+
+#[derive(Args)]
+struct ReplayArgs {
+    /// Replay file (see `play --record`).
+    file: PathBuf,
+    #[command(flatten)]
+    screen: ScreenArgs,
+}
+
+#[derive(Subcommand)]
+enum SpriteCommand {
+    /// Prints a sprite as it is displayed in the game.
+    Preview {
+        /// Sprite file.
+        file: PathBuf,
+        /// Color of the sprite (a name or an ANSI 256-color code).
+        #[arg(long, value_parser = parse_color)]
+        color: Option<ColorCode>,
+    },
+    /// Checks sprite files for what doesn't display as expected.
+    Lint {
+        /// Sprite files.
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+}
+
+/// Parses a color of the command line.
+fn parse_color(color: &str) -> Result<ColorCode, String> {
+    match color.parse() {
+        Ok(code) => Ok(ColorCode(AnsiValue(code))),
+        Err(_) => ColorCode::from_name(color).ok_or_else(|| format!("unknown color `{}`", color)),
+    }
+}
+
+/// Prints the error `msg` and exits.
+fn fail(msg: &str) -> ! {
+    eprintln!("{}", msg);
+    process::exit(1);
+}
+
 fn main() {
+    match Cli::parse().command {
+        None => play(PlayArgs::default(), None),
+        Some(Command::Play(args)) => play(args, None),
+        Some(Command::Replay(args)) => {
+            let replay = Replay::load(&args.file)
+                .unwrap_or_else(|e| fail(&format!("Failed to load the replay: {}", e)));
+            let args = PlayArgs {
+                screen: args.screen,
+                ..PlayArgs::default()
+            };
+            play(args, Some(replay));
+        }
+        Some(Command::Stats) => stats(),
+        Some(Command::Sprite(SpriteCommand::Preview { file, color })) => preview(&file, color),
+        Some(Command::Sprite(SpriteCommand::Lint { files })) => lint(&files),
+        Some(Command::Edit) => edit(),
+    }
+}
+
+/// Plays the game or, if given, plays back `replay`.
+fn play(args: PlayArgs, replay: Option<Replay>) {
+    let config = match &args.screen.config {
+        Some(path) => Config::load(path),
+        None => Config::builtin(),
+    };
+    let config = config.unwrap_or_else(|e| fail(&format!("Failed to load the config: {}", e)));
+    let settings = match Settings::load() {
+        Ok(settings) => settings,
+        Err(e) => fail(&format!("Failed to load the settings: {}", e)),
+    };
+    let (stats, corrupt_stats) = load_stats();
+    let (records, corrupt) = load_records();
+
+    // a replay is played on a screen of the recorded size
+    let (mut cols, mut rows) = termion::terminal_size().expect("Failed to get terminal size.");
+    if let Some(replay) = &replay {
+        if replay.size.width > cols || replay.size.height > rows {
+            fail(&format!(
+                "The replay needs a terminal of at least {}x{}.",
+                replay.size.width, replay.size.height
+            ));
+        }
+        (cols, rows) = (replay.size.width, replay.size.height);
+    }
+
+    let mut game = config
+        .build(cols, rows)
+        .unwrap_or_else(|e| fail(&format!("Failed to load the config: {}", e)));
+
     // get terminal output
-    let mut stdout = io::stdout()
+    let stdout = io::stdout()
         .into_raw_mode()
         .expect("Failed to get a new raw stdout handler");
-    let (cols, rows) = termion::terminal_size().expect("Failed to get terminal size.");
-
-    // load stage layers
-    let ground = Layer::builder(cols, Sprite::from_file("./sprites/ground"))
-        .gap(4)
-        .shift(2)
-        .build();
-    let grass = Layer::builder(cols, Sprite::from_file("./sprites/grass"))
-        .shift(2)
-        .build();
-    let mountains = Layer::builder(cols, Sprite::from_file("./sprites/mountains"))
-        .gap(4)
-        .build();
-    let sky = Layer::builder(cols, Sprite::from_file("./sprites/sky"))
-        .gap(40)
-        .build();
-
-    // creates stage
-    let mut stage = Stage::new(cols, rows);
-    stage.push_layer(ground, color::LightWhite);
-    stage.push_layer(grass, color::Green);
-    stage.set_floor();
-    stage.push_layer(mountains, color::LightBlack);
-    stage.push_layer(sky, color::White);
-
-    // creates player
-    let player = Player::new('O', color::Yellow, stage.floor);
-
-    // set enemies
-    let mut enemies = Enemies::new();
-    let walls_spawn = Pos {
-        col: cols,
-        row: stage.floor,
+    let mut out: Box<dyn Write> = if args.screen.no_color {
+        Box::new(Monochrome::new(stdout))
+    } else {
+        Box::new(stdout)
     };
-    enemies.add_enemy(Walls::new('|', walls_spawn, 2));
-
-    // setup HUD
-    let splash_screen = Splash::new(
-        &stage.size,
-        "Welcome to Crabbit! Press any key to continue.",
-        color::Blue,
-        "Game Paused",
-        color::Magenta,
-    );
-    let hud = Hud::new(splash_screen);
-
-    Game::new(player, stage, enemies, hud).run(&mut stdout);
+
+    game.set_seed(args.seed);
+    game.set_lives(settings.lives);
+    let difficulty = args.difficulty.unwrap_or(settings.difficulty);
+    game.set_difficulty(difficulty, settings.profile(difficulty));
+    game.set_mode(args.mode);
+    let mut runner = Runner::new(game, settings);
+    if let Some(replay) = replay {
+        runner.play_back(replay);
+    } else {
+        runner.track(stats.clone());
+        runner.keep_records(records.clone());
+        if args.autoplay {
+            runner.autoplay();
+        }
+    }
+    if args.record.is_some() {
+        runner.record();
+    }
+    if args.dev {
+        runner.hot_reload(config);
+    }
+    runner.run(&mut out);
+    drop(out);
+
+    if let Some(e) = corrupt_stats {
+        eprintln!("The stats started over: {}", e);
+    }
+    if let Some(e) = corrupt {
+        eprintln!("The records started over: {}", e);
+    }
+    if let Some(e) = runner.divergence() {
+        fail(&format!("The replay failed: {}.", e));
+    }
+    if let (Some(path), Some(replay)) = (args.record, runner.take_recording()) {
+        if let Err(e) = replay.save(&path) {
+            fail(&format!("Failed to save the replay: {}", e));
+        }
+    }
+    match runner.take_stats() {
+        Some(new) if new != stats => {
+            if let Err(e) = new.save() {
+                fail(&format!("Failed to save the stats: {}", e));
+            }
+        }
+        _ => (),
+    }
+    match runner.take_records() {
+        Some(new) if new != records => {
+            if let Err(e) = new.save() {
+                fail(&format!("Failed to save the records: {}", e));
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Loads the stats. A corrupt stats file doesn't stop the game: it is moved aside, the stats
+/// start over and the error is returned to be reported.
+fn load_stats() -> (Stats, Option<StatsError>) {
+    match Stats::load() {
+        Ok(stats) => (stats, None),
+        Err(e @ StatsError::Corrupt(..)) => (Stats::default(), Some(e)),
+        Err(e) => fail(&format!("Failed to load the stats: {}", e)),
+    }
+}
+
+/// Loads the records. A corrupt records file doesn't stop the game: it is moved aside, the
+/// records start over and the error is returned to be reported.
+fn load_records() -> (Records, Option<RecordsError>) {
+    match Records::load() {
+        Ok(records) => (records, None),
+        Err(e @ RecordsError::Corrupt(..)) => (Records::default(), Some(e)),
+        Err(e) => fail(&format!("Failed to load the records: {}", e)),
+    }
+}
+
+/// Prints the stats and the records of the played runs.
+fn stats() {
+    let (stats, corrupt) = load_stats();
+    if let Some(e) = corrupt {
+        eprintln!("The stats started over: {}", e);
+    }
+    println!("{}", stats);
+    let (records, corrupt) = load_records();
+    if let Some(e) = corrupt {
+        eprintln!("The records started over: {}", e);
+    }
+    if !records.is_empty() {
+        println!("\nRecords:\n{}", records);
+    }
+}
+
+/// Prints the sprite at `path` in `color`.
+fn preview(path: &Path, color: Option<ColorCode>) {
+    let sprite = Sprite::load(path).unwrap_or_else(|e| fail(&format!("{}: {}", path.display(), e)));
+    for row in sprite.rows() {
+        let row: String = row.iter().collect();
+        match color {
+            Some(color) => println!("{}{}{}", Fg(color.0), row, Fg(Reset)),
+            None => println!("{}", row),
+        }
+    }
+    let (width, height) = sprite.size();
+    println!("{}x{}", width, height);
+}
+
+/// Prints the problems of the sprites at `paths`. Exits with an error if there is any.
+fn lint(paths: &[PathBuf]) {
+    let mut failed = false;
+    for path in paths {
+        let problems = match fs::read_to_string(path) {
+            Ok(text) => Sprite::lint(&text),
+            Err(e) => vec![e.to_string()],
+        };
+        for problem in &problems {
+            println!("{}: {}", path.display(), problem);
+        }
+        failed |= !problems.is_empty();
+    }
+    if failed {
+        process::exit(1);
+    }
+}
+
+/// Opens the settings file in the text editor, creating it first if it doesn't exist. The edited
+/// settings are checked once the editor is closed.
+fn edit() {
+    let path = Settings::path().unwrap_or_else(|| fail("No configuration directory found."));
+    if !path.exists() {
+        let created = Settings::load().and_then(|settings| settings.save());
+        if let Err(e) = created {
+            fail(&format!("Failed to create the settings: {}", e));
+        }
+    }
+
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or("vi");
+    let status = process::Command::new(program)
+        .args(words)
+        .arg(&path)
+        .status()
+        .unwrap_or_else(|e| fail(&format!("Failed to run the editor `{}`: {}", program, e)));
+    if !status.success() {
+        fail(&format!("The editor `{}` failed ({}).", program, status));
+    }
+
+    if let Err(e) = Settings::load() {
+        fail(&format!("The settings are invalid: {}", e));
+    }
 }
@@ -0,0 +1,217 @@
+//! Best scores of the runs played by the user, saved in the `records.toml` file of the
+//! [`data_dir`].
+//!
+//! A record is kept for each game mode, difficulty and kind of seed (see [`Key`]), one per line:
+//!
+//! ```toml
+//! "endless/normal/random" = 2254
+//! "time-attack/hard/fixed" = 1397
+//! "campaign/random" = 1193
+//! ```
+//!
+//! The file is replaced atomically when saved, so it is never left half written. A file that
+//! can't be read as records isn't overwritten either: it is moved aside (see
+//! [`RecordsError::Corrupt`]) and the records start over.
+//!
+//! [`data_dir`]: crate::dirs::data_dir
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    dirs,
+    game::{Difficulty, Mode},
+};
+
+/// What a record is kept for: the runs of a mode, on a difficulty, with a fixed seed or random
+/// ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key {
+    mode: Mode,
+    difficulty: Option<Difficulty>,
+    seeded: bool,
+}
+
+impl Key {
+    /// Returns the key of the runs in `mode` on `difficulty` (ignored by the campaign, which has
+    /// its own, see [`Mode::LEVELS`]) with a fixed seed if `seeded` or random seeds otherwise.
+    pub fn new(mode: Mode, difficulty: Difficulty, seeded: bool) -> Self {
+        let difficulty = (mode != Mode::Campaign).then_some(difficulty);
+        Key {
+            mode,
+            difficulty,
+            seeded,
+        }
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/", self.mode.name())?;
+        if let Some(difficulty) = self.difficulty {
+            write!(f, "{}/", difficulty.name())?;
+        }
+        write!(f, "{}", if self.seeded { "fixed" } else { "random" })
+    }
+}
+
+/// Best scores by [`Key`].
+///
+/// # Example
+///
+/// ```
+/// use crabbit::{
+///     game::{Difficulty, Mode},
+///     records::{Key, Records},
+/// };
+///
+/// let key = Key::new(Mode::Endless, Difficulty::Hard, false);
+/// let mut records = Records::default();
+///
+/// assert!(records.set(key, 120));
+/// assert!(!records.set(key, 80));
+/// assert_eq!(records.get(key), 120);
+/// assert_eq!(records.get(Key::new(Mode::Endless, Difficulty::Easy, false)), 0);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Records {
+    scores: BTreeMap<String, u32>,
+}
+
+impl Records {
+    // records file name
+    const FILE: &'static str = "records.toml";
+    // extension added to a corrupt records file moved aside
+    const CORRUPT: &'static str = "corrupt";
+    // extension of the file written before it replaces the records file
+    const TEMP: &'static str = "tmp";
+
+    /// Returns the path of the records file.
+    pub fn path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join(Self::FILE))
+    }
+
+    /// Loads the records from the records file.
+    ///
+    /// Returns no records if the file doesn't exist. If the file is corrupt, it is moved aside
+    /// and [`RecordsError::Corrupt`] is returned, so the records can start over.
+    pub fn load() -> Result<Self, RecordsError> {
+        let path = match Self::path() {
+            Some(path) => path,
+            None => return Ok(Self::default()),
+        };
+
+        let text = match fs::read(&path) {
+            Ok(bytes) => String::from_utf8(bytes).map_err(|e| e.to_string()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(RecordsError::Io(path, e)),
+        };
+        match text.and_then(|text| toml::from_str(&text).map_err(|e| e.message().to_string())) {
+            Ok(records) => Ok(records),
+            Err(msg) => {
+                let aside = Self::aside(&path);
+                fs::rename(&path, &aside).map_err(|e| RecordsError::Io(path.clone(), e))?;
+                Err(RecordsError::Corrupt(path, aside, msg))
+            }
+        }
+    }
+
+    /// Saves the records to the records file, replacing it only once they are fully written.
+    pub fn save(&self) -> Result<(), RecordsError> {
+        let path = Self::path().ok_or(RecordsError::NoDir)?;
+        let text = toml::to_string(self).expect("Records are always serializable.");
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| RecordsError::Io(dir.to_path_buf(), e))?;
+        }
+        let temp = path.with_extension(Self::TEMP);
+        let written = File::create(&temp)
+            .and_then(|mut file| {
+                file.write_all(text.as_bytes())?;
+                file.sync_all()
+            })
+            .map_err(|e| RecordsError::Io(temp.clone(), e));
+        if let Err(e) = written {
+            let _ = fs::remove_file(&temp);
+            return Err(e);
+        }
+        fs::rename(&temp, &path).map_err(|e| RecordsError::Io(path, e))
+    }
+
+    /// Returns the path where the corrupt records file at `path` is moved.
+    fn aside(path: &Path) -> PathBuf {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(".");
+        name.push(Self::CORRUPT);
+        path.with_file_name(name)
+    }
+
+    /// Returns the record of `key` (0 if there is none).
+    pub fn get(&self, key: Key) -> u32 {
+        self.scores.get(&key.to_string()).copied().unwrap_or(0)
+    }
+
+    /// Makes `score` the record of `key` if it beats the current one. Returns `true` if it did.
+    pub fn set(&mut self, key: Key, score: u32) -> bool {
+        if score > self.get(key) {
+            self.scores.insert(key.to_string(), score);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns `true` if there are no records.
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+}
+
+impl fmt::Display for Records {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (key, score)) in self.scores.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{:<26}{}", key, score)?;
+        }
+        Ok(())
+    }
+}
+
+/// Errors on loading or saving [`Records`].
+#[derive(Debug)]
+pub enum RecordsError {
+    /// No data directory was found.
+    NoDir,
+    /// Failed to read or write a file.
+    Io(PathBuf, io::Error),
+    /// The records file is invalid: it was moved aside to the second path.
+    Corrupt(PathBuf, PathBuf, String),
+}
+
+impl fmt::Display for RecordsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoDir => write!(f, "no data directory found"),
+            Self::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            Self::Corrupt(path, aside, msg) => write!(
+                f,
+                "{}: {} (moved to {})",
+                path.display(),
+                msg.trim_end(),
+                aside.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RecordsError {}
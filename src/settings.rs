@@ -0,0 +1,120 @@
+//! User settings, saved in the `settings.toml` file of the [`config_dir`].
+//!
+//! [`config_dir`]: crate::dirs::config_dir
+
+use serde::{Deserialize, Serialize};
+use std::{fmt, fs, io, path::PathBuf};
+
+use crate::{
+    dirs,
+    game::{Difficulty, Profile},
+    input::KeyMap,
+};
+
+/// User preferences.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Key bindings.
+    pub keys: KeyMap,
+    /// Mouse clicks make the player jump and select the pause screen entries.
+    pub mouse: bool,
+    /// Asks the terminal to report key releases using the kitty keyboard protocol, so holding
+    /// the jump key makes the player jump higher.
+    pub kitty_keyboard: bool,
+    /// How many lives the player has: each hit takes one, so any hit kills the player if it has a
+    /// single life.
+    pub lives: u16,
+    /// Difficulty of the game.
+    pub difficulty: Difficulty,
+    /// Profile of the custom difficulty.
+    pub custom: Profile,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            keys: KeyMap::default(),
+            mouse: false,
+            kitty_keyboard: false,
+            lives: 1,
+            difficulty: Difficulty::default(),
+            custom: Profile::default(),
+        }
+    }
+}
+
+impl Settings {
+    // settings file name
+    const FILE: &'static str = "settings.toml";
+
+    /// Returns the path of the settings file.
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join(Self::FILE))
+    }
+
+    /// Loads the settings from the settings file.
+    ///
+    /// Returns the default settings if the file doesn't exist.
+    pub fn load() -> Result<Self, SettingsError> {
+        let path = match Self::path() {
+            Some(path) => path,
+            None => return Ok(Self::default()),
+        };
+
+        let settings: Self = match fs::read_to_string(&path) {
+            Ok(text) => toml::from_str(&text).map_err(|e| SettingsError::Parse(path.clone(), e))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(SettingsError::Io(path, e)),
+        };
+        match settings.custom.validate() {
+            Ok(()) => Ok(settings),
+            Err(e) => Err(SettingsError::Invalid(
+                path,
+                format!("custom difficulty: {}", e),
+            )),
+        }
+    }
+
+    /// Returns the profile of the given `difficulty` (the custom profile for the custom one).
+    pub fn profile(&self, difficulty: Difficulty) -> Profile {
+        difficulty.profile().unwrap_or(self.custom)
+    }
+
+    /// Saves the settings to the settings file.
+    pub fn save(&self) -> Result<(), SettingsError> {
+        let path = Self::path().ok_or(SettingsError::NoDir)?;
+        let text = toml::to_string(self).expect("Settings are always serializable.");
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| SettingsError::Io(dir.to_path_buf(), e))?;
+        }
+        fs::write(&path, text).map_err(|e| SettingsError::Io(path, e))
+    }
+}
+
+/// Errors on loading or saving [`Settings`].
+#[derive(Debug)]
+pub enum SettingsError {
+    /// No configuration directory was found.
+    NoDir,
+    /// Failed to read or write a file.
+    Io(PathBuf, io::Error),
+    /// The settings file is invalid.
+    Parse(PathBuf, toml::de::Error),
+    /// Some setting has an invalid value.
+    Invalid(PathBuf, String),
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoDir => write!(f, "no configuration directory found"),
+            Self::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            Self::Parse(path, e) => write!(f, "{}: {}", path.display(), e),
+            Self::Invalid(path, msg) => write!(f, "{}: {}", path.display(), msg),
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {}
@@ -0,0 +1,171 @@
+//! Statistics of the runs played by the user, saved in the `stats.toml` file of the
+//! [`data_dir`].
+//!
+//! Like the [records](crate::records), the file is replaced atomically when saved, and a file that
+//! can't be read as stats is moved aside (see [`StatsError::Corrupt`]) so the stats start over.
+//!
+//! [`data_dir`]: crate::dirs::data_dir
+
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    components::hud::Summary,
+    dirs,
+    game::{GameEvent, Subscriber},
+};
+
+/// Totals of the played runs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stats {
+    /// How many runs were played (until the death of the player).
+    pub runs: u32,
+    /// Best score of a run.
+    pub best: u32,
+    /// Sum of the scores of the runs.
+    pub score: u64,
+    /// How many world steps the player ran.
+    pub distance: u64,
+    /// How many times the player jumped.
+    pub jumps: u64,
+    /// How many obstacles got behind the player.
+    pub passed: u64,
+}
+
+impl Stats {
+    // stats file name
+    const FILE: &'static str = "stats.toml";
+    // extension added to a corrupt stats file moved aside
+    const CORRUPT: &'static str = "corrupt";
+    // extension of the file written before it replaces the stats file
+    const TEMP: &'static str = "tmp";
+
+    /// Returns the path of the stats file.
+    pub fn path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join(Self::FILE))
+    }
+
+    /// Loads the stats from the stats file.
+    ///
+    /// Returns empty stats if the file doesn't exist. If the file is corrupt, it is moved aside
+    /// and [`StatsError::Corrupt`] is returned, so the stats can start over.
+    pub fn load() -> Result<Self, StatsError> {
+        let path = match Self::path() {
+            Some(path) => path,
+            None => return Ok(Self::default()),
+        };
+
+        let text = match fs::read(&path) {
+            Ok(bytes) => String::from_utf8(bytes).map_err(|e| e.to_string()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(StatsError::Io(path, e)),
+        };
+        match text.and_then(|text| toml::from_str(&text).map_err(|e| e.message().to_string())) {
+            Ok(stats) => Ok(stats),
+            Err(msg) => {
+                let aside = Self::aside(&path);
+                fs::rename(&path, &aside).map_err(|e| StatsError::Io(path.clone(), e))?;
+                Err(StatsError::Corrupt(path, aside, msg))
+            }
+        }
+    }
+
+    /// Saves the stats to the stats file, replacing it only once they are fully written.
+    pub fn save(&self) -> Result<(), StatsError> {
+        let path = Self::path().ok_or(StatsError::NoDir)?;
+        let text = toml::to_string(self).expect("Stats are always serializable.");
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| StatsError::Io(dir.to_path_buf(), e))?;
+        }
+        let temp = path.with_extension(Self::TEMP);
+        let written = File::create(&temp)
+            .and_then(|mut file| {
+                file.write_all(text.as_bytes())?;
+                file.sync_all()
+            })
+            .map_err(|e| StatsError::Io(temp.clone(), e));
+        if let Err(e) = written {
+            let _ = fs::remove_file(&temp);
+            return Err(e);
+        }
+        fs::rename(&temp, &path).map_err(|e| StatsError::Io(path, e))
+    }
+
+    /// Returns the path where the corrupt stats file at `path` is moved.
+    fn aside(path: &Path) -> PathBuf {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(".");
+        name.push(Self::CORRUPT);
+        path.with_file_name(name)
+    }
+
+    /// Adds the results of a run.
+    pub fn add(&mut self, run: &Summary) {
+        self.runs = self.runs.saturating_add(1);
+        self.best = self.best.max(run.score);
+        self.score = self.score.saturating_add(run.score.into());
+        self.distance = self.distance.saturating_add(run.distance.into());
+        self.jumps = self.jumps.saturating_add(run.jumps.into());
+    }
+
+    /// Returns the average score of a run.
+    pub fn average(&self) -> u64 {
+        self.score.checked_div(self.runs.into()).unwrap_or(0)
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<18}{}", "Runs:", self.runs)?;
+        writeln!(f, "{:<18}{}", "Best score:", self.best)?;
+        writeln!(f, "{:<18}{}", "Average score:", self.average())?;
+        writeln!(f, "{:<18}{}", "Distance:", self.distance)?;
+        writeln!(f, "{:<18}{}", "Jumps:", self.jumps)?;
+        write!(f, "{:<18}{}", "Obstacles passed:", self.passed)
+    }
+}
+
+impl Subscriber for Stats {
+    /// Counts the passed obstacles.
+    fn notify(&mut self, event: &GameEvent) {
+        if let GameEvent::ObstaclePassed(_) = event {
+            self.passed = self.passed.saturating_add(1);
+        }
+    }
+}
+
+/// Errors on loading or saving [`Stats`].
+#[derive(Debug)]
+pub enum StatsError {
+    /// No data directory was found.
+    NoDir,
+    /// Failed to read or write a file.
+    Io(PathBuf, io::Error),
+    /// The stats file is invalid: it was moved aside to the second path.
+    Corrupt(PathBuf, PathBuf, String),
+}
+
+impl fmt::Display for StatsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoDir => write!(f, "no data directory found"),
+            Self::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            Self::Corrupt(path, aside, msg) => write!(
+                f,
+                "{}: {} (moved to {})",
+                path.display(),
+                msg.trim_end(),
+                aside.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StatsError {}
@@ -0,0 +1,64 @@
+//! Checks of the configurations that don't make a game.
+
+use crabbit::config::Config;
+
+// a configuration whose title is `TITLE`
+const THEME: &str = r"
+[stage]
+floor = 'ground'
+
+[[stage.layers]]
+name = 'ground'
+terrain = 2
+
+[player]
+icon = '@'
+
+[hud]
+title = { text = 'TITLE' }
+pause = { text = 'Paused' }
+";
+
+/// Returns the error of the configuration whose title is `title`, built on a screen of `cols`
+/// columns.
+fn error(title: &str, cols: u16) -> String {
+    let text = THEME.replace("TITLE", title);
+    let e = match Config::parse(&text, "theme.toml", ".") {
+        Ok(config) => config.build(cols, 24).err().unwrap(),
+        Err(e) => e,
+    };
+    e.to_string()
+}
+
+#[test]
+fn empty_texts_are_rejected() {
+    assert_eq!(error("", 80), "theme.toml: hud.title: the text is empty");
+}
+
+#[test]
+fn texts_wider_than_the_screen_are_rejected() {
+    assert_eq!(
+        error("Bienvenue à Crabbit", 18),
+        "theme.toml: hud.title: the text is wider than the screen (18 columns)"
+    );
+}
+
+#[test]
+fn texts_are_measured_in_characters() {
+    let config = Config::parse(
+        &THEME.replace("TITLE", "Bienvenue à Crabbit"),
+        "theme.toml",
+        ".",
+    );
+    assert!(config.unwrap().build(19, 24).is_ok());
+}
+
+#[test]
+fn the_floor_layer_is_not_anchored_to_the_floor() {
+    let text = THEME.replace("terrain = 2", "terrain = 2\nanchor = 'floor'");
+    let e = Config::parse(&text, "theme.toml", ".").err().unwrap();
+    assert_eq!(
+        e.to_string(),
+        "theme.toml: stage.layers[0] (ground): the floor layer can't be anchored to the floor"
+    );
+}
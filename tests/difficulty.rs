@@ -0,0 +1,33 @@
+//! Checks of the difficulty profiles.
+
+use crabbit::game::{Difficulty, Profile};
+
+#[test]
+fn the_levels_are_playable() {
+    for level in Difficulty::ALL {
+        if let Some(profile) = level.profile() {
+            assert_eq!(profile.validate(), Ok(()), "{}", level);
+        }
+    }
+}
+
+#[test]
+fn walls_higher_than_the_jump_are_rejected() {
+    let profile = Profile {
+        max_wall: 5,
+        jump_height: 3,
+        ..Profile::default()
+    };
+    assert_eq!(
+        profile.validate(),
+        Err("the walls must be lower than the highest (boosted) jump, 5 rows".to_string())
+    );
+    assert_eq!(
+        Profile {
+            max_wall: 4,
+            ..profile
+        }
+        .validate(),
+        Ok(())
+    );
+}
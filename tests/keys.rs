@@ -0,0 +1,46 @@
+//! Checks of the key names written to the settings file.
+
+use termion::event::Key;
+
+use crabbit::input::keys::{name, parse};
+
+/// Returns the keys that the terminal can report.
+fn keys() -> Vec<Key> {
+    let mut keys = vec![
+        Key::Backspace,
+        Key::Left,
+        Key::Right,
+        Key::Up,
+        Key::Down,
+        Key::Home,
+        Key::End,
+        Key::PageUp,
+        Key::PageDown,
+        Key::BackTab,
+        Key::Delete,
+        Key::Insert,
+        Key::Null,
+        Key::Esc,
+    ];
+    keys.extend((0..=u8::MAX).map(Key::F));
+    for c in (' '..='~').chain(['\n', '\t', '\r', 'é', 'Ñ', '→', '🦀']) {
+        keys.extend([Key::Ctrl(c), Key::Alt(c)]);
+        if !c.is_ascii_uppercase() {
+            keys.push(Key::Char(c));
+        }
+    }
+    keys
+}
+
+#[test]
+fn every_key_name_is_read_back() {
+    for key in keys() {
+        assert_eq!(parse(&name(key)), Some(key), "name {:?}", name(key));
+    }
+}
+
+#[test]
+fn letters_are_case_insensitive() {
+    assert_eq!(parse("A"), Some(Key::Char('a')));
+    assert_eq!(parse("Shift-Tab"), Some(Key::BackTab));
+}
@@ -0,0 +1,122 @@
+//! Headless runs of the whole game flow (see `Runner::run_headless`): the scenes, the input and
+//! the rendering, without a terminal.
+
+use termion::event::Key;
+
+use crabbit::{
+    config::Config,
+    game::{
+        replay::{Entry, Replay},
+        runner::ScriptedInput,
+        Runner,
+    },
+    settings::Settings,
+};
+
+// seed of the runs
+const SEED: u64 = 7;
+
+/// Returns a runner of the builtin game with a fixed seed, recording the session.
+fn runner() -> Runner {
+    let mut game = Config::builtin().unwrap().build(100, 30).unwrap();
+    game.set_seed(Some(SEED));
+    let mut runner = Runner::new(game, Settings::default());
+    runner.record();
+    runner
+}
+
+/// Returns the input that starts a run from the title screen, followed by `keys` (pressed before
+/// their ticks).
+fn play(keys: &[(u64, Key)]) -> ScriptedInput {
+    keys.iter().fold(
+        ScriptedInput::new().press(0, Key::Char('\n')),
+        |input, &(tick, key)| input.press(tick, key),
+    )
+}
+
+/// Returns how many runs the recorded session had.
+fn runs(replay: &Replay) -> usize {
+    replay
+        .entries
+        .iter()
+        .filter(|(_, entry)| matches!(entry, Entry::Run(_)))
+        .count()
+}
+
+#[test]
+fn seeded_sessions_score_the_same() {
+    let session = || {
+        let mut runner = runner();
+        runner.autoplay();
+        runner.run_headless(&mut Vec::new(), play(&[]), 600);
+        let replay = runner.take_recording().unwrap();
+        (runner.game().score(), replay.entries)
+    };
+
+    let (score, entries) = session();
+    assert!(score > 0);
+    assert_eq!(session(), (score, entries));
+}
+
+#[test]
+fn pause_freezes_the_run() {
+    let mut runner = runner();
+    let mut out = Vec::new();
+    runner.run_headless(&mut out, play(&[(10, Key::Char('p'))]), 40);
+
+    // the run was played from tick 1 until the pause
+    assert_eq!(runner.tick(), 9);
+    assert!(String::from_utf8_lossy(&out).contains("Game Paused"));
+}
+
+#[test]
+fn pause_key_resumes_the_run() {
+    let mut runner = runner();
+    let input = play(&[(10, Key::Char('p')), (20, Key::Char('p'))]);
+    runner.run_headless(&mut Vec::new(), input, 40);
+
+    assert_eq!(runner.tick(), 9 + 19);
+    assert_eq!(runs(&runner.take_recording().unwrap()), 1);
+}
+
+#[test]
+fn restart_starts_a_new_run() {
+    let mut runner = runner();
+    runner.run_headless(&mut Vec::new(), play(&[(10, Key::Char('r'))]), 40);
+    let score = runner.game().score();
+
+    // the new run is played from tick 10 on, scoring as much as the first run of a session played
+    // for as long (whose tick 0 is spent on the title screen)
+    let mut fresh = self::runner();
+    fresh.run_headless(&mut Vec::new(), play(&[]), 40 - 10 + 1);
+    assert_eq!(score, fresh.game().score());
+    assert_eq!(runs(&runner.take_recording().unwrap()), 2);
+}
+
+#[test]
+fn restart_from_the_pause_menu_asks_first() {
+    let mut runner = runner();
+    let input = play(&[
+        (10, Key::Char('p')),
+        (11, Key::Char('r')),
+        (12, Key::Char('n')),
+        (13, Key::Char('r')),
+        (14, Key::Char('y')),
+    ]);
+    runner.run_headless(&mut Vec::new(), input, 40);
+
+    // the answer resumes the run, which is played again after the restart
+    assert_eq!(runner.tick(), 9 + (40 - 15));
+    assert_eq!(runs(&runner.take_recording().unwrap()), 2);
+}
+
+#[test]
+fn quit_ends_the_session() {
+    let mut runner = runner();
+    runner.run_headless(&mut Vec::new(), play(&[(10, Key::Char('q'))]), 1000);
+
+    let replay = runner.take_recording().unwrap();
+    let end = Entry::End(runner.game().score());
+    assert_eq!(runner.tick(), 10);
+    assert_eq!(replay.entries.last(), Some(&(10, end)));
+}